@@ -0,0 +1,165 @@
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A non-negative real world amount stored as a fixed-point number of minor
+/// units, i.e. with [`Amount::DECIMALS`] decimals.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Amount(u128);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseAmountError(String);
+
+impl fmt::Display for ParseAmountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid amount `{}`", self.0)
+    }
+}
+
+impl core::error::Error for ParseAmountError {}
+
+impl Amount {
+    /// Number of decimals every amount is normalized to.
+    pub const DECIMALS: u8 = 2;
+
+    const SCALE: u128 = 10u128.pow(Self::DECIMALS as u32);
+
+    pub fn from_minor_units(minor_units: u128) -> Self {
+        Amount(minor_units)
+    }
+
+    pub fn from_units(units: u64) -> Self {
+        Amount(units as u128 * Self::SCALE)
+    }
+
+    pub fn minor_units(&self) -> u128 {
+        self.0
+    }
+}
+
+impl FromStr for Amount {
+    type Err = ParseAmountError;
+
+    /// Parses amounts as banks print them, e.g. `1000000`, `1.000.000,00` or
+    /// `1,000,000.50`. When both `.` and `,` appear the last one is the
+    /// decimal separator and appears once. A single separator is read as a
+    /// thousands separator if it appears more than once or is followed by
+    /// exactly three digits. Thousands separators must group the integer part
+    /// in threes, so `1,2,3.00` is rejected.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseAmountError(String::from(s));
+        let raw = s.trim().replace(' ', "");
+
+        let (grouping_separator, decimal_separator) = match (raw.rfind('.'), raw.rfind(',')) {
+            (Some(dot), Some(comma)) if dot > comma => (Some(','), Some('.')),
+            (Some(_), Some(_)) => (Some('.'), Some(',')),
+            (Some(position), None) | (None, Some(position)) => {
+                let separator = raw.as_bytes()[position] as char;
+                if raw.matches(separator).count() > 1 || raw.len() - position - 1 == 3 {
+                    (Some(separator), None)
+                } else {
+                    (None, Some(separator))
+                }
+            }
+            (None, None) => (None, None),
+        };
+
+        let (integer, fraction) = match decimal_separator {
+            Some(separator) if raw.matches(separator).count() == 1 => {
+                raw.split_once(separator).ok_or_else(error)?
+            }
+            Some(_) => return Err(error()),
+            None => (raw.as_str(), ""),
+        };
+        let integer = match grouping_separator {
+            Some(separator) => ungroup(integer, separator).ok_or_else(error)?,
+            None => String::from(integer),
+        };
+
+        if integer.is_empty()
+            || fraction.len() > Self::DECIMALS as usize
+            || !integer
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return Err(error());
+        }
+
+        let units: u128 = integer.parse().map_err(|_| error())?;
+        let fraction: u128 = format!("{:0<width$}", fraction, width = Self::DECIMALS as usize)
+            .parse()
+            .map_err(|_| error())?;
+
+        units
+            .checked_mul(Self::SCALE)
+            .and_then(|minor_units| minor_units.checked_add(fraction))
+            .map(Amount)
+            .ok_or_else(error)
+    }
+}
+
+/// Removes the thousands separators of `integer`, `None` unless they split it
+/// into a leading group of one to three digits and groups of three digits.
+fn ungroup(integer: &str, separator: char) -> Option<String> {
+    let mut groups = integer.split(separator);
+    let leading = groups.next()?;
+    if leading.is_empty() || leading.len() > 3 {
+        return None;
+    }
+
+    let mut digits = String::from(leading);
+    for group in groups {
+        if group.len() != 3 {
+            return None;
+        }
+        digits.push_str(group);
+    }
+
+    Some(digits)
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{:0width$}",
+            self.0 / Self::SCALE,
+            self.0 % Self::SCALE,
+            width = Self::DECIMALS as usize
+        )
+    }
+}
+
+impl Serialize for Amount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+struct AmountVisitor;
+
+impl Visitor<'_> for AmountVisitor {
+    type Value = Amount;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a whole number of units or a decimal amount string")
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Amount, E> {
+        Ok(Amount::from_units(value))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Amount, E> {
+        value.parse().map_err(E::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for Amount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Amount, D::Error> {
+        deserializer.deserialize_any(AmountVisitor)
+    }
+}
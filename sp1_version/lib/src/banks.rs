@@ -0,0 +1,187 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloy_primitives::keccak256;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::types::{default_currency, Data, Transaction};
+use crate::Amount;
+
+pub const BCA: &str = "bca";
+pub const MANDIRI: &str = "mandiri";
+pub const BNI: &str = "bni";
+
+/// Channel ids with a known response shape. `hashedChannelId` is the
+/// keccak256 hash of one of them.
+pub const CHANNEL_IDS: [&str; 3] = [BCA, MANDIRI, BNI];
+
+/// The form a channel id is hashed in: trimmed and lowercase, so `BCA` and
+/// `bca` name the same channel.
+pub fn normalize_channel_id(channel_id: &str) -> String {
+    channel_id.trim().to_lowercase()
+}
+
+/// The `hashedChannelId` of `channel_id`, i.e. the keccak256 hash of its
+/// [`normalize_channel_id`] form.
+pub fn hashed_channel_id(channel_id: &str) -> [u8; 32] {
+    keccak256(normalize_channel_id(channel_id)).0
+}
+
+/// A bank API response that can be mapped onto canonical transactions.
+pub trait BankResponse: DeserializeOwned {
+    /// Canonical transactions of the response. Rows that do not name their
+    /// bank are attributed to `channel_id`.
+    fn into_transactions(self, channel_id: &str) -> Vec<Transaction>;
+}
+
+/// bca answers with the canonical `{data:[{id,bank,to,transfer_date,amount}]}`.
+pub type BcaResponse = Data;
+
+impl BankResponse for Data {
+    fn into_transactions(self, _channel_id: &str) -> Vec<Transaction> {
+        self.data
+    }
+}
+
+/// mandiri lists `transactions` with camelCase fields. The canonical field
+/// names are accepted too.
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct MandiriResponse {
+    #[serde(alias = "data")]
+    pub transactions: Vec<MandiriTransaction>,
+}
+
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct MandiriTransaction {
+    #[serde(rename = "transactionId", alias = "id")]
+    pub transaction_id: String,
+    #[serde(default)]
+    pub bank: Option<String>,
+    #[serde(rename = "beneficiaryAccount", alias = "to")]
+    pub beneficiary_account: String,
+    #[serde(rename = "postingDate", alias = "transfer_date")]
+    pub posting_date: String,
+    pub amount: Amount,
+    #[serde(default = "default_currency")]
+    pub currency: String,
+}
+
+impl BankResponse for MandiriResponse {
+    fn into_transactions(self, channel_id: &str) -> Vec<Transaction> {
+        self.transactions
+            .into_iter()
+            .map(|row| Transaction {
+                id: row.transaction_id,
+                bank: row.bank.unwrap_or_else(|| String::from(channel_id)),
+                to: row.beneficiary_account,
+                transfer_date: row.posting_date,
+                amount: row.amount,
+                currency: row.currency,
+            })
+            .collect()
+    }
+}
+
+/// bni lists `mutations` with snake_case fields and a `nominal` amount. The
+/// canonical field names are accepted too.
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct BniResponse {
+    #[serde(alias = "data")]
+    pub mutations: Vec<BniMutation>,
+}
+
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct BniMutation {
+    #[serde(alias = "id")]
+    pub journal_number: String,
+    #[serde(default)]
+    pub bank: Option<String>,
+    #[serde(alias = "to")]
+    pub account_number: String,
+    #[serde(alias = "transfer_date")]
+    pub transaction_date: String,
+    #[serde(alias = "amount")]
+    pub nominal: Amount,
+    #[serde(alias = "currency", default = "default_currency")]
+    pub currency_code: String,
+}
+
+impl BankResponse for BniResponse {
+    fn into_transactions(self, channel_id: &str) -> Vec<Transaction> {
+        self.mutations
+            .into_iter()
+            .map(|row| Transaction {
+                id: row.journal_number,
+                bank: row.bank.unwrap_or_else(|| String::from(channel_id)),
+                to: row.account_number,
+                transfer_date: row.transaction_date,
+                amount: row.nominal,
+                currency: row.currency_code,
+            })
+            .collect()
+    }
+}
+
+/// Parses a bank `transfer_date` such as `2024-10-15 00:00:00` into a unix
+/// timestamp. Bank dates carry no timezone and are read as UTC.
+pub fn parse_transfer_date(transfer_date: &str) -> Option<u32> {
+    let (date, time) = match transfer_date.trim().split_once([' ', 'T']) {
+        Some((date, time)) => (date, time),
+        None => (transfer_date.trim(), "00:00:00"),
+    };
+
+    let parse = |fields: &str| -> Option<Vec<u32>> {
+        fields
+            .split([':', '-'])
+            .map(|field| field.parse::<u32>().ok())
+            .collect()
+    };
+
+    let (year, month, day) = match parse(date)?[..] {
+        [year, month, day] => (year, month, day),
+        _ => return None,
+    };
+    let (hour, minute, second) = match parse(time.trim_end_matches('Z'))?[..] {
+        [hour, minute] => (hour, minute, 0),
+        [hour, minute, second] => (hour, minute, second),
+        _ => return None,
+    };
+
+    let is_leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        2 if is_leap_year => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return None,
+    };
+    if year < 1970 || day == 0 || day > days_in_month || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    // Days since the unix epoch for a proleptic Gregorian date.
+    let (year, month) = if month <= 2 {
+        (year as i64 - 1, month as i64 + 9)
+    } else {
+        (year as i64, month as i64 - 3)
+    };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * month + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146097 + day_of_era - 719468;
+
+    u32::try_from(days * 86400 + (hour * 3600 + minute * 60 + second) as i64).ok()
+}
+
+/// Parses a bank `transfer_date` into the first and last unix timestamp the
+/// transfer could have happened at. A date without a time of day, such as
+/// `2024-10-15`, covers the whole day.
+pub fn parse_transfer_period(transfer_date: &str) -> Option<(u32, u32)> {
+    let earliest = parse_transfer_date(transfer_date)?;
+    if transfer_date.trim().contains([' ', 'T']) {
+        Some((earliest, earliest))
+    } else {
+        Some((earliest, earliest.checked_add(86399)?))
+    }
+}
@@ -1,4 +1,17 @@
-use alloy_sol_types::sol;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+mod amount;
+pub mod banks;
+pub mod rate;
+pub mod types;
+#[cfg(feature = "verify")]
+pub mod verify;
+
+pub use amount::{Amount, ParseAmountError};
+
+use alloy_sol_types::{sol, SolType};
 
 sol! {
     #[derive(Debug)]
@@ -8,25 +21,25 @@ sol! {
         uint32 timestampS;
         uint32 epoch;
     }
-  }
-  
-  sol! {
+}
+
+sol! {
     #[derive(Debug)]
     struct SignedClaim {
         CompleteClaimData claim;
         bytes[] signatures;
     }
-  }
-  
-  sol! {
+}
+
+sol! {
     #[derive(Debug)]
     struct ReclaimProof {
         bytes32 hashedClaimInfo;
         SignedClaim signedClaim;
     }
-  }
-  
-  sol! {
+}
+
+sol! {
     #[derive(Debug)]
     struct OfframpRequestParams {
         address user;
@@ -35,12 +48,66 @@ sol! {
         bytes32 hashedChannelAccount;
         bytes32 hashedChannelId;
     }
-  }
-  
-  sol! {
+}
+
+sol! {
+    #[derive(Debug)]
+    struct ExchangeRate {
+        bytes32 hashedPair;
+        uint256 rate;
+        uint8 decimals;
+        uint32 timestampS;
+        address oracle;
+    }
+}
+
+sol! {
+    #[derive(Debug)]
+    struct TransferWindow {
+        uint32 transferTimestamp;
+        uint32 requestTimestamp;
+        uint32 maxTransferAge;
+    }
+}
+
+sol! {
     #[derive(Debug)]
     struct PublicValuesStruct {
         OfframpRequestParams offrampRequestParams;
         ReclaimProof proof;
+        address[] witnesses;
+        uint32 transactionIndex;
+        bytes32 hashedTransactionId;
+        ExchangeRate exchangeRate;
+        bytes32 hashedCurrency;
+        TransferWindow transferWindow;
+    }
+}
+
+sol! {
+    #[derive(Debug)]
+    struct BatchPublicValuesStruct {
+        PublicValuesStruct[] items;
+    }
+}
+
+/// Whether committed public values hold a `BatchPublicValuesStruct`, which has
+/// to be claimed through `fillOfframpBatch`. A single `PublicValuesStruct` never
+/// decodes as a batch because its leading `user` word is not a valid offset.
+pub fn is_batch_public_values(public_values: &[u8]) -> bool {
+    BatchPublicValuesStruct::abi_decode(public_values, true).is_ok()
+}
+
+/// Decodes committed public values into the items they fill, whether the
+/// program committed a single `PublicValuesStruct` or a batch.
+pub fn decode_public_values(
+    public_values: &[u8],
+) -> Result<alloc::vec::Vec<PublicValuesStruct>, alloy_sol_types::Error> {
+    match BatchPublicValuesStruct::abi_decode(public_values, true) {
+        Ok(batch) => Ok(batch.items),
+        Err(_) => Ok(alloc::vec![PublicValuesStruct::abi_decode(
+            public_values,
+            true
+        )?]),
     }
-  }
\ No newline at end of file
+}
@@ -0,0 +1,21 @@
+use alloy_primitives::U256;
+
+use crate::types::RateQuote;
+use crate::Amount;
+
+/// Maximum distance, in seconds, between the rate quote and the claim it prices.
+pub const MAX_RATE_AGE_S: u32 = 60 * 60;
+
+/// Converts a real world amount, given in minor units with
+/// [`Amount::DECIMALS`] decimals, into token units. The rate is the number of
+/// token units per real world unit scaled by `10^decimals`; the result is
+/// rounded down so a fill never pays out more than was transferred.
+///
+/// `None` when `10^(decimals + Amount::DECIMALS)` or `amount * rate` overflows
+/// a `uint256`, which bounds the decimals a quote may carry.
+pub fn convert(amount_real_world: U256, quote: &RateQuote) -> Option<U256> {
+    let exponent = U256::from(u32::from(quote.decimals) + u32::from(Amount::DECIMALS));
+    let scale = U256::from(10u64).checked_pow(exponent)?;
+
+    Some(amount_real_world.checked_mul(U256::from(quote.rate))? / scale)
+}
@@ -0,0 +1,133 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+use crate::Amount;
+
+/// Currency assumed for bank rows that do not report one.
+pub const DEFAULT_CURRENCY: &str = "IDR";
+
+pub(crate) fn default_currency() -> String {
+    String::from(DEFAULT_CURRENCY)
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct TransferRequest {
+    pub id: String,
+    pub bank: String,
+    pub address: String,
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct Transaction {
+    pub id: String,
+    pub bank: String,
+    pub to: String,
+    pub transfer_date: String,
+    pub amount: Amount,
+    #[serde(default = "default_currency")]
+    pub currency: String,
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct Data {
+    pub data: Vec<Transaction>,
+}
+
+/// `type` of a response match whose `value` is a substring of the attested
+/// response, as opposed to a `regex` match whose `value` is only a pattern.
+pub const CONTAINS_MATCH: &str = "contains";
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct ResponseMatch {
+    #[serde(rename = "type")]
+    pub type_resp: String,
+    #[serde(rename = "value")]
+    pub value_resp: String,
+}
+
+impl ResponseMatch {
+    /// Whether `value_resp` is part of the attested response, so the bank
+    /// rows it holds are proven.
+    pub fn is_attested(&self) -> bool {
+        self.type_resp == CONTAINS_MATCH
+    }
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct ResponseRedaction {
+    #[serde(rename = "jsonPath", default)]
+    pub json_path: String,
+    #[serde(rename = "xPath", default)]
+    pub x_path: String,
+    #[serde(default)]
+    pub regex: String,
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct Parameters {
+    pub body: String,
+    pub method: String,
+    #[serde(rename = "responseMatches")]
+    pub response_matches: Vec<ResponseMatch>,
+    #[serde(rename = "responseRedactions", default)]
+    pub response_redactions: Vec<ResponseRedaction>,
+    #[serde(default)]
+    pub url: String,
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct Claim {
+    pub epoch: u32,
+    pub identifier: String,
+    pub owner: String,
+    #[serde(rename = "timestampS")]
+    pub timestamp: u32,
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct ClaimInfo {
+    pub provider: String,
+    pub parameters: String,
+    pub context: String,
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct Context {
+    #[serde(rename = "contextAddress")]
+    pub context_address: String,
+    #[serde(rename = "contextMessage", default)]
+    pub context_message: String,
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct SignedClaim {
+    pub claim: Claim,
+    pub signatures: Vec<String>,
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct Proof {
+    #[serde(rename = "claimInfo")]
+    pub claim_info: ClaimInfo,
+    #[serde(rename = "signedClaim")]
+    pub signed_claim: SignedClaim,
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct RateQuote {
+    pub pair: String,
+    pub rate: u64,
+    pub decimals: u8,
+    #[serde(rename = "timestampS")]
+    pub timestamp: u32,
+    pub signature: String,
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct OfframpInput {
+    pub proof: String,
+    pub transaction_id: String,
+    pub channel_id: String,
+    pub request_timestamp: u32,
+}
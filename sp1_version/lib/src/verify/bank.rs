@@ -0,0 +1,108 @@
+use alloc::vec::Vec;
+use alloy_primitives::B256;
+
+use super::reclaim::ClaimError;
+use crate::banks::{self, BankResponse, BcaResponse, BniResponse, MandiriResponse};
+use crate::types;
+
+/// Maps an attested bank response onto canonical transactions with the parser
+/// of the bank whose channel id hashes to `hashed_channel_id`.
+pub fn parse_bank_response(
+    hashed_channel_id: B256,
+    response: &str,
+) -> Result<Vec<types::Transaction>, ClaimError> {
+    fn parse<R: BankResponse>(
+        channel_id: &str,
+        response: &str,
+    ) -> Result<Vec<types::Transaction>, ClaimError> {
+        serde_json::from_str::<R>(response)
+            .map(|response| response.into_transactions(channel_id))
+            .map_err(|_| ClaimError::InvalidResponse)
+    }
+
+    let channel_id = banks::CHANNEL_IDS
+        .into_iter()
+        .find(|channel_id| banks::hashed_channel_id(channel_id) == hashed_channel_id)
+        .ok_or(ClaimError::UnknownBank)?;
+
+    match channel_id {
+        banks::BCA => parse::<BcaResponse>(channel_id, response),
+        banks::MANDIRI => parse::<MandiriResponse>(channel_id, response),
+        banks::BNI => parse::<BniResponse>(channel_id, response),
+        _ => Err(ClaimError::UnknownBank),
+    }
+}
+
+/// Finds the row of an attested bank response that matches `transaction_id`
+/// on `channel_id`, returning its position in the response and the row. Only
+/// `contains` matches are searched, the value of any other match is not part
+/// of the attested response.
+pub fn find_transaction(
+    parameters: &types::Parameters,
+    transaction_id: &str,
+    channel_id: &str,
+) -> Result<(u32, types::Transaction), ClaimError> {
+    let channel_id = banks::normalize_channel_id(channel_id);
+    let hashed_channel_id = B256::from(banks::hashed_channel_id(&channel_id));
+    let mut attested = false;
+
+    for response_match in parameters
+        .response_matches
+        .iter()
+        .filter(|response_match| response_match.is_attested())
+    {
+        attested = true;
+        let found = parse_bank_response(hashed_channel_id, &response_match.value_resp)?
+            .into_iter()
+            .enumerate()
+            .find(|(_, transaction)| {
+                transaction.id == transaction_id
+                    && banks::normalize_channel_id(&transaction.bank) == channel_id
+            });
+
+        if let Some((index, transaction)) = found {
+            return Ok((index as u32, transaction));
+        }
+    }
+
+    if attested {
+        Err(ClaimError::TransactionNotFound)
+    } else {
+        Err(ClaimError::UnattestedResponse)
+    }
+}
+
+/// Parses a bank `transfer_date` into the first and last unix timestamp the
+/// transfer could have happened at, see [`banks::parse_transfer_period`].
+pub fn parse_transfer_period(transfer_date: &str) -> Result<(u32, u32), ClaimError> {
+    banks::parse_transfer_period(transfer_date).ok_or(ClaimError::InvalidTransferDate)
+}
+
+/// Checks the transfer, made between `earliest_transfer_timestamp` and
+/// `latest_transfer_timestamp`, could have happened after the offramp was
+/// requested, before it was attested, and no more than `max_transfer_age`
+/// seconds before the claim. Banks that only date transfers to the day give a
+/// whole day, so a transfer made on the day of the request passes.
+///
+/// `request_timestamp` is chosen by the prover and committed in the public
+/// values, the contract does not check it against the request block.
+pub fn verify_transfer_window(
+    (earliest_transfer_timestamp, latest_transfer_timestamp): (u32, u32),
+    request_timestamp: u32,
+    claim_timestamp: u32,
+    max_transfer_age: u32,
+) -> Result<(), ClaimError> {
+    if latest_transfer_timestamp < request_timestamp {
+        return Err(ClaimError::TransferBeforeRequest);
+    }
+
+    if earliest_transfer_timestamp > claim_timestamp {
+        return Err(ClaimError::TransferAfterClaim);
+    }
+
+    if claim_timestamp - latest_transfer_timestamp.min(claim_timestamp) > max_transfer_age {
+        return Err(ClaimError::StaleTransfer);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! The checks a zkVM program runs before committing an offramp, shared by the
+//! SP1 program and the zkRust version so both commit the same public values.
+
+pub mod bank;
+pub mod rate;
+pub mod reclaim;
+
+pub use reclaim::ClaimError;
+
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use alloy_sol_types::SolType;
+
+use crate::{
+    banks, types, BatchPublicValuesStruct, CompleteClaimData, ExchangeRate, OfframpRequestParams,
+    PublicValuesStruct, ReclaimProof, SignedClaim, TransferWindow,
+};
+
+/// Verifies every offramp in `inputs` and returns the public values to commit:
+/// a `PublicValuesStruct` for a single offramp, as `fillOfframp` expects, or a
+/// `BatchPublicValuesStruct` for `fillOfframpBatch`.
+pub fn commit_offramps(
+    witnesses: &[Address],
+    oracles: &[Address],
+    quote: &types::RateQuote,
+    max_transfer_age: u32,
+    inputs: &[types::OfframpInput],
+) -> Result<Vec<u8>, ClaimError> {
+    if inputs.is_empty() {
+        return Err(ClaimError::NoOfframp);
+    }
+
+    let oracle: Address = rate::verify_rate_quote(quote, oracles)?;
+    let exchange_rate = ExchangeRate {
+        hashedPair: keccak256(&quote.pair),
+        rate: U256::from(quote.rate),
+        decimals: quote.decimals,
+        timestampS: quote.timestamp,
+        oracle,
+    };
+
+    let mut items: Vec<PublicValuesStruct> = Vec::new();
+    for input in inputs.iter() {
+        let public_values =
+            prove_offramp(input, witnesses, quote, &exchange_rate, max_transfer_age)?;
+
+        if items
+            .iter()
+            .any(|item| item.proof.hashedClaimInfo == public_values.proof.hashedClaimInfo)
+        {
+            return Err(ClaimError::DuplicateClaim);
+        }
+
+        items.push(public_values);
+    }
+
+    Ok(if items.len() == 1 {
+        PublicValuesStruct::abi_encode(&items.remove(0))
+    } else {
+        BatchPublicValuesStruct::abi_encode(&BatchPublicValuesStruct { items })
+    })
+}
+
+/// Verifies a single offramp against the Reclaim witnesses, the rate quote and
+/// the transfer window, and builds the public values it fills.
+pub fn prove_offramp(
+    input: &types::OfframpInput,
+    witnesses: &[Address],
+    quote: &types::RateQuote,
+    exchange_rate: &ExchangeRate,
+    max_transfer_age: u32,
+) -> Result<PublicValuesStruct, ClaimError> {
+    let proof: types::Proof =
+        serde_json::from_str(&input.proof).map_err(|_| ClaimError::InvalidProof)?;
+    let parameters: types::Parameters =
+        serde_json::from_str(&proof.claim_info.parameters).map_err(|_| ClaimError::InvalidProof)?;
+    let (transaction_index, transaction) =
+        bank::find_transaction(&parameters, &input.transaction_id, &input.channel_id)?;
+
+    let hashed_claim_info: B256 =
+        reclaim::verify_claim_identifier(&proof.claim_info, &proof.signed_claim.claim)?;
+    let owner: Address = reclaim::verify_claim_data(&proof.signed_claim.claim)?;
+    reclaim::verify_witness_signatures(&proof.signed_claim, witnesses)?;
+    let user: Address = reclaim::verify_context(&proof.claim_info)?;
+
+    let hashed_channel_id = B256::from(banks::hashed_channel_id(&transaction.bank));
+    let hashed_channel_account: B256 = keccak256(&transaction.to);
+    rate::verify_rate_freshness(quote, proof.signed_claim.claim.timestamp)?;
+
+    let (transfer_timestamp, transferred_by) =
+        bank::parse_transfer_period(&transaction.transfer_date)?;
+    bank::verify_transfer_window(
+        (transfer_timestamp, transferred_by),
+        input.request_timestamp,
+        proof.signed_claim.claim.timestamp,
+        max_transfer_age,
+    )?;
+    rate::verify_currency(quote, &transaction.currency)?;
+
+    let amount_real_world: U256 = U256::from(transaction.amount.minor_units());
+    let amount: U256 = rate::convert(amount_real_world, quote)?;
+    let hashed_transaction_id: B256 = keccak256(&transaction.id);
+    let hashed_currency: B256 = keccak256(transaction.currency.to_uppercase());
+
+    let signatures = proof
+        .signed_claim
+        .signatures
+        .iter()
+        .map(|v| v.as_str().to_string().into())
+        .collect::<Vec<Bytes>>();
+
+    Ok(PublicValuesStruct {
+        offrampRequestParams: OfframpRequestParams {
+            user,
+            amount,
+            amountRealWorld: amount_real_world,
+            hashedChannelAccount: hashed_channel_account,
+            hashedChannelId: hashed_channel_id,
+        },
+        proof: ReclaimProof {
+            hashedClaimInfo: hashed_claim_info,
+            signedClaim: SignedClaim {
+                claim: CompleteClaimData {
+                    epoch: proof.signed_claim.claim.epoch,
+                    identifier: hashed_claim_info,
+                    owner,
+                    timestampS: proof.signed_claim.claim.timestamp,
+                },
+                signatures,
+            },
+        },
+        witnesses: witnesses.to_vec(),
+        transactionIndex: transaction_index,
+        hashedTransactionId: hashed_transaction_id,
+        exchangeRate: exchange_rate.clone(),
+        hashedCurrency: hashed_currency,
+        transferWindow: TransferWindow {
+            transferTimestamp: transfer_timestamp,
+            requestTimestamp: input.request_timestamp,
+            maxTransferAge: max_transfer_age,
+        },
+    })
+}
@@ -0,0 +1,60 @@
+use alloc::format;
+use alloc::string::String;
+use alloy_primitives::{Address, U256};
+
+use super::reclaim::{self, ClaimError};
+use crate::rate::{self, MAX_RATE_AGE_S};
+use crate::types;
+
+/// Builds the message an oracle signs for a quote, i.e.
+/// `pair\nrate\ndecimals\ntimestampS`.
+pub fn serialize_rate_quote(quote: &types::RateQuote) -> String {
+    format!(
+        "{}\n{}\n{}\n{}",
+        quote.pair, quote.rate, quote.decimals, quote.timestamp
+    )
+}
+
+/// Checks the quote is well formed and signed by one of `oracles`, returning
+/// the signing oracle.
+pub fn verify_rate_quote(
+    quote: &types::RateQuote,
+    oracles: &[Address],
+) -> Result<Address, ClaimError> {
+    if quote.rate == 0 || quote.pair.is_empty() {
+        return Err(ClaimError::InvalidRate);
+    }
+
+    let oracle = reclaim::recover_signer(&serialize_rate_quote(quote), &quote.signature)
+        .map_err(|_| ClaimError::InvalidRate)?;
+    if !oracles.contains(&oracle) {
+        return Err(ClaimError::UnknownOracle);
+    }
+
+    Ok(oracle)
+}
+
+/// Checks the quote was issued within [`MAX_RATE_AGE_S`] of the claim.
+pub fn verify_rate_freshness(
+    quote: &types::RateQuote,
+    claim_timestamp: u32,
+) -> Result<(), ClaimError> {
+    if quote.timestamp.abs_diff(claim_timestamp) > MAX_RATE_AGE_S {
+        return Err(ClaimError::StaleRate);
+    }
+
+    Ok(())
+}
+
+/// Checks the quote prices `currency`, i.e. its pair is `<currency>/<token>`.
+pub fn verify_currency(quote: &types::RateQuote, currency: &str) -> Result<(), ClaimError> {
+    match quote.pair.split_once('/') {
+        Some((base, _)) if base.eq_ignore_ascii_case(currency) => Ok(()),
+        _ => Err(ClaimError::CurrencyMismatch),
+    }
+}
+
+/// Converts a real world amount into token units, see [`rate::convert`].
+pub fn convert(amount_real_world: U256, quote: &types::RateQuote) -> Result<U256, ClaimError> {
+    rate::convert(amount_real_world, quote).ok_or(ClaimError::AmountOverflow)
+}
@@ -0,0 +1,195 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloy_primitives::{keccak256, Address, FixedBytes, PrimitiveSignature, B256};
+use core::fmt;
+use hex::FromHex;
+
+use crate::types;
+
+/// Reasons the guest rejects a Reclaim proof. The discriminant is the error
+/// code reported when the program aborts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClaimError {
+    IdentifierMismatch = 1,
+    InvalidIdentifier = 2,
+    InvalidOwner = 3,
+    InvalidTimestamp = 4,
+    InvalidEpoch = 5,
+    MissingSignature = 6,
+    InvalidSignature = 7,
+    UnknownWitness = 8,
+    DuplicateWitness = 9,
+    InvalidContext = 10,
+    InvalidResponse = 11,
+    TransactionNotFound = 12,
+    DuplicateClaim = 13,
+    InvalidRate = 14,
+    UnknownOracle = 15,
+    StaleRate = 16,
+    CurrencyMismatch = 17,
+    InvalidTransferDate = 18,
+    TransferBeforeRequest = 19,
+    TransferAfterClaim = 20,
+    StaleTransfer = 21,
+    UnknownBank = 22,
+    AmountOverflow = 23,
+    InvalidProof = 24,
+    NoOfframp = 25,
+    UnattestedResponse = 26,
+}
+
+impl fmt::Display for ClaimError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            ClaimError::IdentifierMismatch => "claim identifier does not match hashed claim info",
+            ClaimError::InvalidIdentifier => "claim identifier is not a 32 byte hex string",
+            ClaimError::InvalidOwner => "claim owner is not a 20 byte hex address",
+            ClaimError::InvalidTimestamp => "claim timestamp is zero",
+            ClaimError::InvalidEpoch => "claim epoch is zero",
+            ClaimError::MissingSignature => "claim is not signed by any witness",
+            ClaimError::InvalidSignature => "claim signature is malformed",
+            ClaimError::UnknownWitness => "claim is signed by an unknown witness",
+            ClaimError::DuplicateWitness => "claim is signed twice by the same witness",
+            ClaimError::InvalidContext => "claim context does not carry a recipient address",
+            ClaimError::InvalidResponse => "claim response is not a bank transaction list",
+            ClaimError::TransactionNotFound => "claim response does not contain the transaction",
+            ClaimError::DuplicateClaim => "claim appears more than once in the batch",
+            ClaimError::InvalidRate => "exchange rate quote is malformed",
+            ClaimError::UnknownOracle => "exchange rate is signed by an unknown oracle",
+            ClaimError::StaleRate => "exchange rate is too far from the claim timestamp",
+            ClaimError::CurrencyMismatch => "exchange rate does not price the transfer currency",
+            ClaimError::InvalidTransferDate => "transfer date is not a valid date and time",
+            ClaimError::TransferBeforeRequest => "transfer happened before the offramp request",
+            ClaimError::TransferAfterClaim => "transfer date is later than the claim timestamp",
+            ClaimError::StaleTransfer => "transfer is older than the allowed window",
+            ClaimError::UnknownBank => "channel id has no known bank response parser",
+            ClaimError::AmountOverflow => "converted amount overflows uint256",
+            ClaimError::InvalidProof => "Reclaim proof or its parameters are not valid JSON",
+            ClaimError::NoOfframp => "no offramp to prove",
+            ClaimError::UnattestedResponse => {
+                "no response match is a `contains` match of the attested response"
+            }
+        };
+        write!(f, "ClaimError({}): {}", *self as u8, reason)
+    }
+}
+
+impl core::error::Error for ClaimError {}
+
+/// Computes the Reclaim claim identifier, i.e.
+/// `keccak256(provider\nparameters\ncontext)`.
+pub fn hash_claim_info(claim_info: &types::ClaimInfo) -> B256 {
+    let mut encoded_claim_info: Vec<u8> = Vec::new();
+    encoded_claim_info.extend_from_slice(claim_info.provider.as_bytes());
+    encoded_claim_info.extend_from_slice(b"\n");
+    encoded_claim_info.extend_from_slice(claim_info.parameters.as_bytes());
+    encoded_claim_info.extend_from_slice(b"\n");
+    encoded_claim_info.extend_from_slice(claim_info.context.as_bytes());
+
+    keccak256(encoded_claim_info)
+}
+
+/// Parses the claim identifier and checks it is the hash of `claim_info`.
+pub fn verify_claim_identifier(
+    claim_info: &types::ClaimInfo,
+    claim: &types::Claim,
+) -> Result<B256, ClaimError> {
+    let identifier = claim
+        .identifier
+        .parse::<FixedBytes<32>>()
+        .map_err(|_| ClaimError::InvalidIdentifier)?;
+
+    if identifier != hash_claim_info(claim_info) {
+        return Err(ClaimError::IdentifierMismatch);
+    }
+
+    Ok(identifier)
+}
+
+/// Checks the owner, timestamp and epoch that witnesses sign alongside the
+/// identifier, returning the parsed owner address.
+pub fn verify_claim_data(claim: &types::Claim) -> Result<Address, ClaimError> {
+    let owner = claim
+        .owner
+        .strip_prefix("0x")
+        .and_then(|address| <[u8; 20]>::from_hex(address).ok())
+        .map(Address::from)
+        .ok_or(ClaimError::InvalidOwner)?;
+
+    if claim.timestamp == 0 {
+        return Err(ClaimError::InvalidTimestamp);
+    }
+
+    if claim.epoch == 0 {
+        return Err(ClaimError::InvalidEpoch);
+    }
+
+    Ok(owner)
+}
+
+/// Reads the recipient from the `contextAddress` the attestor embedded in the
+/// claim context. The context is part of the identifier, so witnesses sign it.
+pub fn verify_context(claim_info: &types::ClaimInfo) -> Result<Address, ClaimError> {
+    let context: types::Context =
+        serde_json::from_str(&claim_info.context).map_err(|_| ClaimError::InvalidContext)?;
+
+    context
+        .context_address
+        .strip_prefix("0x")
+        .and_then(|address| <[u8; 20]>::from_hex(address).ok())
+        .map(Address::from)
+        .filter(|address| *address != Address::ZERO)
+        .ok_or(ClaimError::InvalidContext)
+}
+
+/// Builds the message a Reclaim witness signs for a claim, i.e.
+/// `identifier\nowner\ntimestampS\nepoch` with hex fields lowercased.
+pub fn serialize_claim_data(claim: &types::Claim) -> String {
+    format!(
+        "{}\n{}\n{}\n{}",
+        claim.identifier.to_lowercase(),
+        claim.owner.to_lowercase(),
+        claim.timestamp,
+        claim.epoch
+    )
+}
+
+/// Recovers the address that produced an EIP-191 `signature` over `message`.
+pub fn recover_signer(message: &str, signature: &str) -> Result<Address, ClaimError> {
+    let signature_bytes = Vec::<u8>::from_hex(signature.trim_start_matches("0x"))
+        .map_err(|_| ClaimError::InvalidSignature)?;
+    let signature = PrimitiveSignature::try_from(signature_bytes.as_slice())
+        .map_err(|_| ClaimError::InvalidSignature)?;
+
+    signature
+        .recover_address_from_msg(message)
+        .map_err(|_| ClaimError::InvalidSignature)
+}
+
+/// Checks that the claim carries at least one signature and that every
+/// signature was produced by a distinct member of `witnesses`.
+pub fn verify_witness_signatures(
+    signed_claim: &types::SignedClaim,
+    witnesses: &[Address],
+) -> Result<(), ClaimError> {
+    if signed_claim.signatures.is_empty() {
+        return Err(ClaimError::MissingSignature);
+    }
+
+    let message = serialize_claim_data(&signed_claim.claim);
+    let mut signers: Vec<Address> = Vec::new();
+
+    for signature in signed_claim.signatures.iter() {
+        let signer = recover_signer(&message, signature)?;
+        if !witnesses.contains(&signer) {
+            return Err(ClaimError::UnknownWitness);
+        }
+        if signers.contains(&signer) {
+            return Err(ClaimError::DuplicateWitness);
+        }
+        signers.push(signer);
+    }
+
+    Ok(())
+}
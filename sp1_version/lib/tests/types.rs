@@ -0,0 +1,327 @@
+use alloy_sol_types::private::{Address, Bytes, FixedBytes, U256};
+use alloy_sol_types::SolType;
+use zktransfer_lib::banks::{BankResponse, BniResponse, MandiriResponse};
+use zktransfer_lib::types::{Data, OfframpInput, Parameters, Proof, DEFAULT_CURRENCY};
+use zktransfer_lib::{
+    decode_public_values, is_batch_public_values, Amount, BatchPublicValuesStruct,
+    CompleteClaimData, ExchangeRate, OfframpRequestParams, PublicValuesStruct, ReclaimProof,
+    SignedClaim, TransferWindow,
+};
+
+const PROOF_JSON: &str = r#"{
+    "claimInfo": {
+        "provider": "http",
+        "parameters": "{\"body\":\"\",\"method\":\"GET\",\"responseMatches\":[{\"type\":\"regex\",\"value\":\"{\\\"data\\\":[{\\\"id\\\":\\\"121\\\",\\\"bank\\\":\\\"bca\\\",\\\"to\\\":\\\"111111\\\",\\\"transfer_date\\\":\\\"2024-10-15 00:00:00\\\",\\\"amount\\\":1000000}]}\"}],\"responseRedactions\":[{\"jsonPath\":\"$.data\",\"xPath\":\"\",\"regex\":\"\"}],\"url\":\"https://mock.blocknaut.xyz/api/v2/mutation?bank=bca&id=121\"}",
+        "context": "{\"contextAddress\":\"0x0000000000000000000000000000000000000001\",\"contextMessage\":\"121\"}"
+    },
+    "signedClaim": {
+        "claim": {
+            "identifier": "0x1111111111111111111111111111111111111111111111111111111111111111",
+            "owner": "0x0000000000000000000000000000000000000002",
+            "timestampS": 1728950500,
+            "epoch": 1
+        },
+        "signatures": ["0xaa", "0xbb"]
+    }
+}"#;
+
+#[test]
+fn proof_round_trips_through_json() {
+    let proof: Proof = serde_json::from_str(PROOF_JSON).unwrap();
+    assert_eq!(proof.signed_claim.claim.timestamp, 1728950500);
+    assert_eq!(proof.signed_claim.signatures.len(), 2);
+
+    let encoded = serde_json::to_string(&proof).unwrap();
+    assert_eq!(serde_json::from_str::<Proof>(&encoded).unwrap(), proof);
+}
+
+#[test]
+fn parameters_and_response_parse_from_claim_info() {
+    let proof: Proof = serde_json::from_str(PROOF_JSON).unwrap();
+    let parameters: Parameters = serde_json::from_str(&proof.claim_info.parameters).unwrap();
+    assert_eq!(parameters.response_redactions[0].json_path, "$.data");
+    assert!(parameters.url.ends_with("bank=bca&id=121"));
+
+    let response: Data = serde_json::from_str(&parameters.response_matches[0].value_resp).unwrap();
+    let transaction = &response.data[0];
+    assert_eq!(transaction.amount, Amount::from_units(1000000));
+    assert_eq!(transaction.currency, DEFAULT_CURRENCY);
+
+    let encoded = serde_json::to_string(&response).unwrap();
+    assert_eq!(serde_json::from_str::<Data>(&encoded).unwrap(), response);
+}
+
+#[test]
+fn parameters_without_optional_fields_parse() {
+    let parameters: Parameters =
+        serde_json::from_str(r#"{"body":"","method":"GET","responseMatches":[]}"#).unwrap();
+    assert!(parameters.response_redactions.is_empty());
+    assert!(parameters.url.is_empty());
+}
+
+#[test]
+fn transaction_amount_accepts_bank_formats() {
+    let response: Data = serde_json::from_str(
+        r#"{"data":[{"id":"1","bank":"bni","to":"1","transfer_date":"","amount":"1.000.000,50","currency":"idr"}]}"#,
+    )
+    .unwrap();
+    assert_eq!(response.data[0].amount, Amount::from_minor_units(100000050));
+    assert_eq!(response.data[0].currency, "idr");
+}
+
+#[test]
+fn amount_parses_and_displays() {
+    let cases = [
+        ("1000000", "1000000.00"),
+        ("1.000.000,00", "1000000.00"),
+        ("1,000,000.50", "1000000.50"),
+        ("1.000", "1000.00"),
+        ("1,5", "1.50"),
+        ("12.345.678", "12345678.00"),
+        ("12,345,678.9", "12345678.90"),
+        ("100.000,5", "100000.50"),
+    ];
+    for (raw, display) in cases {
+        let amount: Amount = raw.parse().unwrap();
+        assert_eq!(amount.to_string(), display);
+        assert_eq!(display.parse::<Amount>().unwrap(), amount);
+    }
+
+    for raw in [
+        "",
+        ".5",
+        "abc",
+        "1.2.3,456",
+        "-1",
+        "1,2,3.00",
+        "1.000.000,5,0",
+        "1,000.000.50",
+        "1000,000.00",
+        ",100.00",
+        "1.00.000",
+        "1,0000",
+    ] {
+        assert!(raw.parse::<Amount>().is_err(), "{raw} should not parse");
+    }
+}
+
+#[test]
+fn offramp_input_round_trips_through_json() {
+    let input = OfframpInput {
+        proof: String::from(PROOF_JSON),
+        transaction_id: String::from("121"),
+        channel_id: String::from("bca"),
+        request_timestamp: 1728950400,
+    };
+    let encoded = serde_json::to_string(&input).unwrap();
+    assert_eq!(
+        serde_json::from_str::<OfframpInput>(&encoded).unwrap(),
+        input
+    );
+}
+
+fn public_values() -> PublicValuesStruct {
+    PublicValuesStruct {
+        offrampRequestParams: OfframpRequestParams {
+            user: Address::repeat_byte(1),
+            amount: U256::from(64u64),
+            amountRealWorld: U256::from(100000000u64),
+            hashedChannelAccount: FixedBytes::repeat_byte(2),
+            hashedChannelId: FixedBytes::repeat_byte(3),
+        },
+        proof: ReclaimProof {
+            hashedClaimInfo: FixedBytes::repeat_byte(4),
+            signedClaim: SignedClaim {
+                claim: CompleteClaimData {
+                    identifier: FixedBytes::repeat_byte(4),
+                    owner: Address::repeat_byte(5),
+                    timestampS: 1728950500,
+                    epoch: 1,
+                },
+                signatures: vec![Bytes::from_static(b"0xaa")],
+            },
+        },
+        witnesses: vec![Address::repeat_byte(6)],
+        transactionIndex: 0,
+        hashedTransactionId: FixedBytes::repeat_byte(7),
+        exchangeRate: ExchangeRate {
+            hashedPair: FixedBytes::repeat_byte(8),
+            rate: U256::from(64u64),
+            decimals: 0,
+            timestampS: 1728950400,
+            oracle: Address::repeat_byte(9),
+        },
+        hashedCurrency: FixedBytes::repeat_byte(10),
+        transferWindow: TransferWindow {
+            transferTimestamp: 1728950400,
+            requestTimestamp: 1728950000,
+            maxTransferAge: 86400,
+        },
+    }
+}
+
+#[test]
+fn public_values_round_trip_through_abi() {
+    let encoded = PublicValuesStruct::abi_encode(&public_values());
+    let decoded = PublicValuesStruct::abi_decode(&encoded, true).unwrap();
+    assert_eq!(PublicValuesStruct::abi_encode(&decoded), encoded);
+    assert_eq!(decoded.offrampRequestParams.user, Address::repeat_byte(1));
+
+    let batch = BatchPublicValuesStruct {
+        items: vec![public_values(), public_values()],
+    };
+    let encoded = BatchPublicValuesStruct::abi_encode(&batch);
+    let decoded = BatchPublicValuesStruct::abi_decode(&encoded, true).unwrap();
+    assert_eq!(decoded.items.len(), 2);
+    assert_eq!(BatchPublicValuesStruct::abi_encode(&decoded), encoded);
+}
+
+#[test]
+fn single_public_values_are_not_a_batch() {
+    let single = PublicValuesStruct::abi_encode(&public_values());
+    assert!(!is_batch_public_values(&single));
+
+    let batch = BatchPublicValuesStruct::abi_encode(&BatchPublicValuesStruct {
+        items: vec![public_values()],
+    });
+    assert!(is_batch_public_values(&batch));
+}
+
+#[test]
+fn public_values_decode_into_their_items() {
+    let single = PublicValuesStruct::abi_encode(&public_values());
+    let items = decode_public_values(&single).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(PublicValuesStruct::abi_encode(&items[0]), single);
+
+    let batch = BatchPublicValuesStruct::abi_encode(&BatchPublicValuesStruct {
+        items: vec![public_values(), public_values()],
+    });
+    assert_eq!(decode_public_values(&batch).unwrap().len(), 2);
+
+    assert!(decode_public_values(&single[..64]).is_err());
+}
+
+#[test]
+fn bank_responses_map_onto_transactions() {
+    let mandiri: MandiriResponse = serde_json::from_str(
+        r#"{"transactions":[{"transactionId":"123","beneficiaryAccount":"333333","postingDate":"2024-10-15 00:00:00","amount":"3.000.000,00"}]}"#,
+    )
+    .unwrap();
+    let bni: BniResponse = serde_json::from_str(
+        r#"{"mutations":[{"journal_number":"124","account_number":"444444","transaction_date":"2024-10-15 00:00:00","nominal":1000000,"currency_code":"IDR"}]}"#,
+    )
+    .unwrap();
+
+    let mandiri = mandiri.into_transactions("mandiri");
+    assert_eq!(mandiri[0].id, "123");
+    assert_eq!(mandiri[0].bank, "mandiri");
+    assert_eq!(mandiri[0].to, "333333");
+    assert_eq!(mandiri[0].amount, Amount::from_units(3000000));
+    assert_eq!(mandiri[0].currency, DEFAULT_CURRENCY);
+
+    let bni = bni.into_transactions("bni");
+    assert_eq!(bni[0].id, "124");
+    assert_eq!(bni[0].bank, "bni");
+    assert_eq!(bni[0].transfer_date, "2024-10-15 00:00:00");
+    assert_eq!(bni[0].amount, Amount::from_units(1000000));
+}
+
+#[test]
+fn bank_responses_accept_the_canonical_shape() {
+    let canonical = r#"{"data":[{"id":"121","bank":"bca","to":"111111","transfer_date":"2024-10-15 00:00:00","amount":1000000}]}"#;
+    let expected = serde_json::from_str::<Data>(canonical).unwrap().data;
+
+    let mandiri = serde_json::from_str::<MandiriResponse>(canonical).unwrap();
+    let bni = serde_json::from_str::<BniResponse>(canonical).unwrap();
+    assert_eq!(mandiri.into_transactions("mandiri"), expected);
+    assert_eq!(bni.into_transactions("bni"), expected);
+}
+
+#[test]
+fn channel_ids_hash_in_normal_form() {
+    use zktransfer_lib::banks::{hashed_channel_id, normalize_channel_id, BCA};
+
+    assert_eq!(normalize_channel_id(" BCA "), BCA);
+    assert_eq!(hashed_channel_id("Bca"), hashed_channel_id(BCA));
+    assert_ne!(hashed_channel_id("bni"), hashed_channel_id(BCA));
+}
+
+#[test]
+fn conversion_rounds_down_and_checks_overflow() {
+    use zktransfer_lib::rate::convert;
+    use zktransfer_lib::types::RateQuote;
+
+    let quote = |rate: u64, decimals: u8| RateQuote {
+        pair: String::from("IDR/USD"),
+        rate,
+        decimals,
+        timestamp: 1728950400,
+        signature: String::new(),
+    };
+
+    // 10,000.00 IDR at 64 token units per IDR, scaled by 10^3.
+    assert_eq!(
+        convert(U256::from(1_000_000u64), &quote(64_000, 3)),
+        Some(U256::from(640_000u64))
+    );
+    assert_eq!(
+        convert(U256::from(199u64), &quote(1, 0)),
+        Some(U256::from(1u64))
+    );
+    assert_eq!(convert(U256::from(1u64), &quote(1, 75)), Some(U256::ZERO));
+    assert_eq!(convert(U256::from(1u64), &quote(1, 76)), None);
+    assert_eq!(convert(U256::MAX, &quote(2, 0)), None);
+}
+
+#[test]
+fn date_only_transfers_cover_the_whole_day() {
+    use zktransfer_lib::banks::parse_transfer_period;
+
+    assert_eq!(
+        parse_transfer_period("2024-10-15 08:30:00"),
+        Some((1728981000, 1728981000))
+    );
+    assert_eq!(
+        parse_transfer_period("2024-10-15"),
+        Some((1728950400, 1729036799))
+    );
+    assert_eq!(parse_transfer_period("2024-13-01"), None);
+}
+
+#[cfg(feature = "verify")]
+#[test]
+fn same_day_transfers_fall_in_the_window() {
+    use zktransfer_lib::verify::bank::verify_transfer_window;
+    use zktransfer_lib::verify::reclaim::ClaimError;
+
+    let day = (1728950400, 1729036799);
+    let requested_at = 1728981000;
+    let claimed_at = 1728990000;
+
+    assert_eq!(
+        verify_transfer_window(day, requested_at, claimed_at, 3600),
+        Ok(())
+    );
+    assert_eq!(
+        verify_transfer_window(
+            (1728981000, 1728981000),
+            requested_at + 1,
+            claimed_at,
+            86400
+        ),
+        Err(ClaimError::TransferBeforeRequest)
+    );
+    assert_eq!(
+        verify_transfer_window(day, 1729036800, 1729040000, 86400),
+        Err(ClaimError::TransferBeforeRequest)
+    );
+    assert_eq!(
+        verify_transfer_window((1729036800, 1729123199), requested_at, claimed_at, 86400),
+        Err(ClaimError::TransferAfterClaim)
+    );
+    assert_eq!(
+        verify_transfer_window(day, 1728900000, 1729040000 + 3600, 3600),
+        Err(ClaimError::StaleTransfer)
+    );
+}
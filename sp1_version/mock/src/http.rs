@@ -0,0 +1,113 @@
+use axum::body::Bytes;
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use zktransfer_lib::types;
+
+use crate::Mock;
+
+/// A JSON `{"error": ...}` answer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiError(pub StatusCode, pub String);
+
+impl ApiError {
+    pub fn new(status: StatusCode, error: &str) -> Self {
+        ApiError(status, String::from(error))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MutationQuery {
+    result: Option<String>,
+    bank: Option<String>,
+    id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RateQuery {
+    pair: Option<String>,
+    #[serde(rename = "timestampS")]
+    timestamp: Option<u32>,
+}
+
+/// Routes of the Node mock bank API and attestor.
+pub fn router(mock: Arc<Mock>) -> Router {
+    Router::new()
+        .route("/api/v2/mutation", get(mutation))
+        .route("/generateTransferProof", post(generate_transfer_proof))
+        .route("/api/v2/rate", get(rate_quote))
+        .with_state(mock)
+}
+
+/// Mirrors `GET /api/v2/mutation` of the Node mock.
+async fn mutation(
+    State(mock): State<Arc<Mock>>,
+    Query(query): Query<MutationQuery>,
+) -> Result<Json<Value>, ApiError> {
+    match (
+        query.result.as_deref(),
+        query.bank.as_deref(),
+        query.id.as_deref(),
+    ) {
+        (Some("error"), _, _) => Err(ApiError::new(StatusCode::UNAUTHORIZED, "No Authorization")),
+        (Some("notfound"), _, _) => Ok(Json(json!({ "data": [] }))),
+        (_, Some(bank), Some(id)) => {
+            let row = mock.find_transaction(bank, id).cloned();
+            Ok(Json(json!({ "data": [row.unwrap_or(json!({}))] })))
+        }
+        (Some("all"), _, _) => Ok(Json(mock.mutations.clone())),
+        _ => Err(ApiError::new(
+            StatusCode::NOT_FOUND,
+            "'bank' and 'id' is mandatory",
+        )),
+    }
+}
+
+async fn generate_transfer_proof(
+    State(mock): State<Arc<Mock>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<types::Proof>, ApiError> {
+    if !headers.contains_key(header::AUTHORIZATION) {
+        return Err(ApiError::new(StatusCode::FORBIDDEN, "No credentials sent!"));
+    }
+
+    let request: types::TransferRequest = serde_json::from_slice(&body)
+        .map_err(|error| ApiError(StatusCode::BAD_REQUEST, error.to_string()))?;
+
+    mock.transfer_proof(&request).map(Json)
+}
+
+/// Serves `GET /api/v2/rate?pair=IDR/USD&timestampS=...`, a quote signed
+/// by the test oracle.
+async fn rate_quote(
+    State(mock): State<Arc<Mock>>,
+    query: Option<Query<RateQuery>>,
+) -> Result<Json<types::RateQuote>, ApiError> {
+    let Some(Query(RateQuery {
+        pair,
+        timestamp: Some(timestamp),
+    })) = query
+    else {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "'timestampS' is mandatory",
+        ));
+    };
+
+    Ok(Json(mock.sign_rate_quote(
+        pair.as_deref().unwrap_or("IDR/USD"),
+        timestamp,
+    )))
+}
@@ -0,0 +1,155 @@
+pub mod http;
+pub mod signer;
+
+use alloy_primitives::{hex, keccak256};
+use serde_json::{json, Value};
+use zktransfer_lib::banks;
+use zktransfer_lib::types;
+
+use axum::http::StatusCode;
+use http::ApiError;
+use signer::TestSigner;
+
+/// Seed of the test witness signing every mock Reclaim claim.
+pub const WITNESS_SEED: &str = "jackramp mock witness";
+
+/// Seed of the test oracle signing every mock rate quote.
+pub const ORACLE_SEED: &str = "jackramp mock oracle";
+
+/// Seconds between a bank transfer and the claim attesting it.
+pub const CLAIM_DELAY_S: u32 = 300;
+
+/// The mock bank transactions shipped with the Node mock.
+pub const MUTATIONS: &str = include_str!("../../../zktls/mutation.json");
+
+/// Stand-in for the bank API and the zkTLS attestor. Proofs are signed by a
+/// test witness instead of going through Reclaim, and only depend on the
+/// request and the bank data, so the same transfer always gets the same proof.
+pub struct Mock {
+    /// URL the mock is reachable at, embedded in the attested request.
+    pub public_url: String,
+    /// Bank transactions, as `{"data":[...]}`.
+    pub mutations: Value,
+    pub witness: TestSigner,
+    pub oracle: TestSigner,
+    pub rate: u64,
+    pub rate_decimals: u8,
+}
+
+impl Mock {
+    pub fn new(public_url: &str, mutations: Value) -> Self {
+        Mock {
+            public_url: String::from(public_url.trim_end_matches('/')),
+            mutations,
+            witness: TestSigner::from_seed(WITNESS_SEED),
+            oracle: TestSigner::from_seed(ORACLE_SEED),
+            rate: 64,
+            rate_decimals: 0,
+        }
+    }
+
+    /// Finds the mock bank transaction `id` of `bank`.
+    pub fn find_transaction(&self, bank: &str, id: &str) -> Option<&Value> {
+        self.mutations["data"].as_array()?.iter().find(|row| {
+            row["id"].as_str() == Some(id)
+                && row["bank"].as_str() == Some(&banks::normalize_channel_id(bank))
+        })
+    }
+
+    /// Builds a Reclaim-shaped proof of the transfer in `request`, claimed
+    /// [`CLAIM_DELAY_S`] after the transfer and signed by the test witness.
+    pub fn transfer_proof(
+        &self,
+        request: &types::TransferRequest,
+    ) -> Result<types::Proof, ApiError> {
+        if !is_address(&request.address) {
+            return Err(ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "'address' must be a 0x-prefixed address",
+            ));
+        }
+
+        let row = self
+            .find_transaction(&request.bank, &request.id)
+            .ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, "transaction not found"))?;
+        let transfer_timestamp = row["transfer_date"]
+            .as_str()
+            .and_then(banks::parse_transfer_date)
+            .ok_or_else(|| {
+                ApiError::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "transaction has no valid transfer_date",
+                )
+            })?;
+
+        let parameters = types::Parameters {
+            body: String::new(),
+            method: String::from("GET"),
+            response_matches: vec![types::ResponseMatch {
+                type_resp: String::from(types::CONTAINS_MATCH),
+                value_resp: json!({ "data": [row] }).to_string(),
+            }],
+            response_redactions: vec![types::ResponseRedaction {
+                json_path: String::from("$.data"),
+                ..Default::default()
+            }],
+            url: format!(
+                "{}/api/v2/mutation?bank={}&id={}",
+                self.public_url, request.bank, request.id
+            ),
+        };
+        let context = types::Context {
+            context_address: request.address.to_lowercase(),
+            context_message: request.id.clone(),
+        };
+        let claim_info = types::ClaimInfo {
+            provider: String::from("http"),
+            parameters: serde_json::to_string(&parameters).expect("parameters serialize"),
+            context: serde_json::to_string(&context).expect("context serializes"),
+        };
+
+        let identifier = hex::encode_prefixed(keccak256(format!(
+            "{}\n{}\n{}",
+            claim_info.provider, claim_info.parameters, claim_info.context
+        )));
+        let claim = types::Claim {
+            epoch: 1,
+            identifier,
+            owner: format!("{:#x}", self.witness.address()),
+            timestamp: transfer_timestamp + CLAIM_DELAY_S,
+        };
+        let message = format!(
+            "{}\n{}\n{}\n{}",
+            claim.identifier, claim.owner, claim.timestamp, claim.epoch
+        );
+
+        Ok(types::Proof {
+            claim_info,
+            signed_claim: types::SignedClaim {
+                signatures: vec![self.witness.sign_message(&message)],
+                claim,
+            },
+        })
+    }
+
+    pub fn sign_rate_quote(&self, pair: &str, timestamp: u32) -> types::RateQuote {
+        let message = format!(
+            "{}\n{}\n{}\n{}",
+            pair, self.rate, self.rate_decimals, timestamp
+        );
+
+        types::RateQuote {
+            pair: String::from(pair),
+            rate: self.rate,
+            decimals: self.rate_decimals,
+            timestamp,
+            signature: self.oracle.sign_message(&message),
+        }
+    }
+}
+
+fn is_address(value: &str) -> bool {
+    value
+        .strip_prefix("0x")
+        .is_some_and(|digits| digits.len() == 40 && digits.chars().all(|c| c.is_ascii_hexdigit()))
+}
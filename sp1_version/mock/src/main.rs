@@ -0,0 +1,49 @@
+use axum::extract::Request;
+use axum::middleware::{self, Next};
+use axum::response::Response;
+use mock::http::router;
+use mock::{Mock, MUTATIONS};
+use std::sync::Arc;
+use std::{env, fs};
+
+#[tokio::main]
+async fn main() {
+    let port = env::var("MOCK_PORT").unwrap_or(String::from("7787"));
+    let public_url = env::var("MOCK_PUBLIC_URL").unwrap_or(format!("http://localhost:{}", port));
+    let mutations = match env::var("MOCK_MUTATIONS") {
+        Ok(path) => {
+            fs::read_to_string(&path).unwrap_or_else(|e| panic!("failed to read {}: {}", path, e))
+        }
+        Err(_) => String::from(MUTATIONS),
+    };
+    let mutations = serde_json::from_str(&mutations).expect("mutations are JSON");
+
+    let mut mock = Mock::new(&public_url, mutations);
+    if let Ok(rate) = env::var("MOCK_RATE") {
+        mock.rate = rate.parse().expect("MOCK_RATE is an integer");
+    }
+    if let Ok(decimals) = env::var("MOCK_RATE_DECIMALS") {
+        mock.rate_decimals = decimals.parse().expect("MOCK_RATE_DECIMALS is an integer");
+    }
+
+    let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", port))
+        .await
+        .unwrap_or_else(|e| panic!("failed to listen on port {}: {}", port, e));
+    println!("Mock API listening on port {}", port);
+    println!("witness: {:#x}", mock.witness.address());
+    println!("oracle: {:#x}", mock.oracle.address());
+
+    let app = router(Arc::new(mock)).layer(middleware::from_fn(log));
+    axum::serve(listener, app)
+        .await
+        .expect("mock server failed");
+}
+
+async fn log(request: Request, next: Next) -> Response {
+    let method = request.method().clone();
+    let path = String::from(request.uri().path());
+    let response = next.run(request).await;
+    println!("{} {} {}", method, path, response.status().as_u16());
+
+    response
+}
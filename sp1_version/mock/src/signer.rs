@@ -0,0 +1,38 @@
+use alloy_primitives::{eip191_hash_message, hex, keccak256, Address, PrimitiveSignature};
+use k256::ecdsa::SigningKey;
+
+/// A well-known key for signing test claims and rate quotes. Never use it for
+/// anything holding value.
+#[derive(Clone)]
+pub struct TestSigner {
+    key: SigningKey,
+    address: Address,
+}
+
+impl TestSigner {
+    /// Derives the key as `keccak256(seed)`, so every run signs alike.
+    pub fn from_seed(seed: &str) -> Self {
+        let key = SigningKey::from_slice(keccak256(seed).as_slice())
+            .expect("keccak256 of the seed is a valid secp256k1 key");
+        let address = Address::from_private_key(&key);
+
+        TestSigner { key, address }
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Signs `message` as an Ethereum signed message, the way Reclaim witnesses
+    /// and rate oracles do, returning the 65 byte `r || s || v` signature.
+    pub fn sign_message(&self, message: &str) -> String {
+        let (signature, recovery_id) = self
+            .key
+            .sign_prehash_recoverable(eip191_hash_message(message).as_slice())
+            .expect("signing a 32 byte prehash succeeds");
+        let signature =
+            PrimitiveSignature::from_signature_and_parity(signature, recovery_id.is_y_odd());
+
+        hex::encode_prefixed(signature.as_bytes())
+    }
+}
@@ -0,0 +1,169 @@
+use alloy_primitives::{hex, keccak256, Address, PrimitiveSignature};
+use axum::body::{to_bytes, Body};
+use axum::http::{Request, StatusCode};
+use mock::http::router;
+use mock::{Mock, CLAIM_DELAY_S, MUTATIONS};
+use std::sync::Arc;
+use tower::ServiceExt;
+use zktransfer_lib::banks::{self, BankResponse, BcaResponse};
+use zktransfer_lib::types;
+
+const RECIPIENT: &str = "0x00000000000000000000000000000000000000aa";
+
+fn mock() -> Mock {
+    Mock::new(
+        "http://localhost:7787",
+        serde_json::from_str(MUTATIONS).unwrap(),
+    )
+}
+
+/// Sends `request` to the mock's router, returning the status and body.
+async fn send(mock: Mock, request: Request<Body>) -> (StatusCode, Vec<u8>) {
+    let response = router(Arc::new(mock)).oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+    (status, body.to_vec())
+}
+
+fn get(uri: &str) -> Request<Body> {
+    Request::get(uri).body(Body::empty()).unwrap()
+}
+
+fn transfer_request() -> types::TransferRequest {
+    types::TransferRequest {
+        id: String::from("121"),
+        bank: String::from("bca"),
+        address: String::from(RECIPIENT),
+    }
+}
+
+fn recover(message: &str, signature: &str) -> Address {
+    let signature = hex::decode(signature).unwrap();
+    PrimitiveSignature::try_from(signature.as_slice())
+        .unwrap()
+        .recover_address_from_msg(message)
+        .unwrap()
+}
+
+#[test]
+fn transfer_proof_is_signed_by_the_test_witness() {
+    let mock = mock();
+    let proof = mock.transfer_proof(&transfer_request()).unwrap();
+    let claim = &proof.signed_claim.claim;
+
+    let hashed_claim_info = keccak256(format!(
+        "{}\n{}\n{}",
+        proof.claim_info.provider, proof.claim_info.parameters, proof.claim_info.context
+    ));
+    assert_eq!(claim.identifier, hex::encode_prefixed(hashed_claim_info));
+
+    let message = format!(
+        "{}\n{}\n{}\n{}",
+        claim.identifier, claim.owner, claim.timestamp, claim.epoch
+    );
+    assert_eq!(
+        recover(&message, &proof.signed_claim.signatures[0]),
+        mock.witness.address()
+    );
+
+    let context: types::Context = serde_json::from_str(&proof.claim_info.context).unwrap();
+    assert_eq!(context.context_address, RECIPIENT);
+
+    let parameters: types::Parameters = serde_json::from_str(&proof.claim_info.parameters).unwrap();
+    let response: BcaResponse =
+        serde_json::from_str(&parameters.response_matches[0].value_resp).unwrap();
+    let transaction = &response.into_transactions("bca")[0];
+    assert_eq!(transaction.id, "121");
+    assert_eq!(
+        claim.timestamp,
+        banks::parse_transfer_date(&transaction.transfer_date).unwrap() + CLAIM_DELAY_S
+    );
+}
+
+#[test]
+fn transfer_proof_is_deterministic() {
+    let mock = mock();
+    assert_eq!(
+        mock.transfer_proof(&transfer_request()).unwrap(),
+        mock.transfer_proof(&transfer_request()).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn transfer_proof_endpoint_checks_the_request() {
+    let body = serde_json::to_string(&transfer_request()).unwrap();
+
+    let unauthorized = Request::post("/generateTransferProof")
+        .body(Body::from(body.clone()))
+        .unwrap();
+    assert_eq!(send(mock(), unauthorized).await.0, StatusCode::FORBIDDEN);
+
+    let authorized = Request::post("/generateTransferProof")
+        .header("Authorization", "Bearer test")
+        .body(Body::from(body))
+        .unwrap();
+    let (status, response) = send(mock(), authorized).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        serde_json::from_slice::<types::Proof>(&response).unwrap(),
+        mock().transfer_proof(&transfer_request()).unwrap()
+    );
+
+    let malformed = Request::post("/generateTransferProof")
+        .header("Authorization", "Bearer test")
+        .body(Body::from("{"))
+        .unwrap();
+    assert_eq!(send(mock(), malformed).await.0, StatusCode::BAD_REQUEST);
+
+    let mut unknown = transfer_request();
+    unknown.id = String::from("999");
+    assert_eq!(
+        mock().transfer_proof(&unknown).unwrap_err().0,
+        StatusCode::NOT_FOUND
+    );
+
+    let mut invalid = transfer_request();
+    invalid.address = String::from("0x1234");
+    assert_eq!(
+        mock().transfer_proof(&invalid).unwrap_err().0,
+        StatusCode::BAD_REQUEST
+    );
+}
+
+#[tokio::test]
+async fn mutation_endpoint_mirrors_the_node_mock() {
+    let (status, found) = send(mock(), get("/api/v2/mutation?bank=BCA&id=121")).await;
+    assert_eq!(status, StatusCode::OK);
+    let data: types::Data = serde_json::from_slice(&found).unwrap();
+    assert_eq!(data.data.len(), 1);
+    assert_eq!(data.data[0].to, "111111");
+
+    let error = send(mock(), get("/api/v2/mutation?result=error")).await;
+    assert_eq!(error.0, StatusCode::UNAUTHORIZED);
+
+    let missing = send(mock(), get("/api/v2/mutation")).await;
+    assert_eq!(missing.0, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn rate_quote_is_signed_by_the_test_oracle() {
+    let oracle = mock().oracle.address();
+    let (status, response) = send(
+        mock(),
+        get("/api/v2/rate?pair=IDR%2FUSD&timestampS=1728950700"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let quote: types::RateQuote = serde_json::from_slice(&response).unwrap();
+
+    assert_eq!(quote.pair, "IDR/USD");
+    let message = format!(
+        "{}\n{}\n{}\n{}",
+        quote.pair, quote.rate, quote.decimals, quote.timestamp
+    );
+    assert_eq!(recover(&message, &quote.signature), oracle);
+
+    let missing = send(mock(), get("/api/v2/rate?pair=IDR%2FUSD")).await;
+    assert_eq!(missing.0, StatusCode::BAD_REQUEST);
+}
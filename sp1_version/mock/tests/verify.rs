@@ -0,0 +1,124 @@
+//! Runs the zkVM program's checks natively on proofs and quotes from the mock.
+
+use alloy_primitives::{keccak256, Address, U256};
+use mock::{Mock, CLAIM_DELAY_S, MUTATIONS};
+use zktransfer_lib::banks;
+use zktransfer_lib::decode_public_values;
+use zktransfer_lib::types;
+use zktransfer_lib::verify::bank::find_transaction;
+use zktransfer_lib::verify::{commit_offramps, ClaimError};
+
+const RECIPIENT: &str = "0x00000000000000000000000000000000000000aa";
+
+const MAX_TRANSFER_AGE: u32 = 24 * 60 * 60;
+
+fn mock() -> Mock {
+    Mock::new(
+        "http://localhost:7787",
+        serde_json::from_str(MUTATIONS).unwrap(),
+    )
+}
+
+fn input(mock: &Mock, id: &str) -> (types::OfframpInput, u32) {
+    let proof = mock
+        .transfer_proof(&types::TransferRequest {
+            id: String::from(id),
+            bank: String::from("bca"),
+            address: String::from(RECIPIENT),
+        })
+        .unwrap();
+    let claim_timestamp = proof.signed_claim.claim.timestamp;
+
+    let input = types::OfframpInput {
+        proof: serde_json::to_string(&proof).unwrap(),
+        transaction_id: String::from(id),
+        channel_id: String::from("BCA"),
+        request_timestamp: claim_timestamp - CLAIM_DELAY_S,
+    };
+
+    (input, claim_timestamp)
+}
+
+fn commit(
+    mock: &Mock,
+    inputs: &[types::OfframpInput],
+    quote: &types::RateQuote,
+) -> Result<Vec<u8>, ClaimError> {
+    commit_offramps(
+        &[mock.witness.address()],
+        &[mock.oracle.address()],
+        quote,
+        MAX_TRANSFER_AGE,
+        inputs,
+    )
+}
+
+#[test]
+fn mock_proofs_commit_the_requested_offramp() {
+    let mock = mock();
+    let (input, claim_timestamp) = input(&mock, "121");
+    let quote = mock.sign_rate_quote("IDR/USD", claim_timestamp);
+
+    let bytes = commit(&mock, &[input], &quote).unwrap();
+    let items = decode_public_values(&bytes).unwrap();
+    assert_eq!(items.len(), 1);
+
+    let params = &items[0].offrampRequestParams;
+    assert_eq!(params.user, RECIPIENT.parse::<Address>().unwrap());
+    assert_eq!(
+        params.hashedChannelId.0,
+        banks::hashed_channel_id(banks::BCA)
+    );
+    assert_eq!(params.hashedChannelId, keccak256("bca"));
+    assert_eq!(
+        params.amount,
+        zktransfer_lib::rate::convert(params.amountRealWorld, &quote).unwrap()
+    );
+    assert!(params.amount > U256::ZERO);
+}
+
+#[test]
+fn mock_proofs_are_checked() {
+    let mock = mock();
+    let (input, claim_timestamp) = input(&mock, "121");
+
+    let stale = mock.sign_rate_quote("IDR/USD", claim_timestamp - 2 * 60 * 60);
+    assert_eq!(
+        commit(&mock, std::slice::from_ref(&input), &stale),
+        Err(ClaimError::StaleRate)
+    );
+
+    let quote = mock.sign_rate_quote("IDR/USD", claim_timestamp);
+    assert_eq!(
+        commit(&mock, &[input.clone(), input.clone()], &quote),
+        Err(ClaimError::DuplicateClaim)
+    );
+    assert_eq!(commit(&mock, &[], &quote), Err(ClaimError::NoOfframp));
+
+    let unknown_witness = commit_offramps(
+        &[mock.oracle.address()],
+        &[mock.oracle.address()],
+        &quote,
+        MAX_TRANSFER_AGE,
+        &[input],
+    );
+    assert_eq!(unknown_witness, Err(ClaimError::UnknownWitness));
+}
+
+#[test]
+fn only_contains_matches_are_searched() {
+    let mock = mock();
+    let (input, _) = input(&mock, "121");
+    let proof: types::Proof = serde_json::from_str(&input.proof).unwrap();
+    let mut parameters: types::Parameters =
+        serde_json::from_str(&proof.claim_info.parameters).unwrap();
+
+    let (_, transaction) = find_transaction(&parameters, "121", " BCA ").unwrap();
+    assert_eq!(transaction.id, "121");
+
+    parameters.response_matches[0].type_resp = String::from("regex");
+    assert_eq!(
+        find_transaction(&parameters, "121", "bca"),
+        Err(ClaimError::UnattestedResponse)
+    );
+}
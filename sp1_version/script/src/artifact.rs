@@ -0,0 +1,129 @@
+use aligned_sdk::core::types::AlignedVerificationData;
+use sp1_sdk::SP1ProofWithPublicValues;
+use std::io::{self, Read};
+use std::path::Path;
+
+use crate::error::OfframpError;
+use crate::onchain::PlonkFixture;
+use crate::types;
+use zktransfer_lib::verify::reclaim::{self, ClaimError};
+
+/// Writes a Reclaim proof as returned by the attestor.
+pub fn save_reclaim_proof(path: &Path, proof: &types::Proof) -> Result<(), OfframpError> {
+    let raw_proof =
+        serde_json::to_string_pretty(proof).map_err(|e| OfframpError::Schema(e.to_string()))?;
+    std::fs::write(path, raw_proof)?;
+
+    Ok(())
+}
+
+/// Reads a Reclaim proof saved by `save_reclaim_proof` or taken from the
+/// attestor, from stdin when `path` is `-`, and checks it before proving.
+pub fn load_reclaim_proof(path: &Path) -> Result<types::Proof, OfframpError> {
+    let raw_proof = if path == Path::new("-") {
+        let mut raw_proof = String::new();
+        io::stdin().read_to_string(&mut raw_proof)?;
+        raw_proof
+    } else {
+        std::fs::read_to_string(path)?
+    };
+
+    let proof = serde_json::from_str(&raw_proof).map_err(|error| {
+        OfframpError::Input(format!(
+            "invalid Reclaim proof file {}: {}",
+            path.display(),
+            error
+        ))
+    })?;
+    validate_reclaim_proof(&proof)?;
+
+    Ok(proof)
+}
+
+/// Runs the checks of the zkVM program that need no witness or rate quote,
+/// so a malformed file fails here instead of aborting inside the prover.
+pub fn validate_reclaim_proof(proof: &types::Proof) -> Result<(), OfframpError> {
+    let claim = &proof.signed_claim.claim;
+
+    serde_json::from_str::<types::Parameters>(&proof.claim_info.parameters)
+        .map_err(|_| ClaimError::InvalidProof)?;
+    reclaim::verify_claim_identifier(&proof.claim_info, claim)?;
+    reclaim::verify_claim_data(claim)?;
+    reclaim::verify_context(&proof.claim_info)?;
+
+    if proof.signed_claim.signatures.is_empty() {
+        return Err(ClaimError::MissingSignature.into());
+    }
+    let message = reclaim::serialize_claim_data(claim);
+    for signature in proof.signed_claim.signatures.iter() {
+        reclaim::recover_signer(&message, signature)?;
+    }
+
+    Ok(())
+}
+
+/// Writes an SP1 proof and its public values in the bincode format used by sp1.
+pub fn save_sp1_proof(path: &Path, proof: &SP1ProofWithPublicValues) -> Result<(), OfframpError> {
+    proof
+        .save(path)
+        .map_err(|e| OfframpError::Proving(e.to_string()))
+}
+
+/// Reads an SP1 proof written by `save_sp1_proof`.
+pub fn load_sp1_proof(path: &Path) -> Result<SP1ProofWithPublicValues, OfframpError> {
+    SP1ProofWithPublicValues::load(path).map_err(|error| {
+        OfframpError::Input(format!(
+            "invalid SP1 proof file {}: {}",
+            path.display(),
+            error
+        ))
+    })
+}
+
+/// Writes the batch inclusion data returned once Aligned verified a proof.
+pub fn save_aligned_verification_data(
+    path: &Path,
+    data: &AlignedVerificationData,
+) -> Result<(), OfframpError> {
+    let raw_data =
+        serde_json::to_string_pretty(data).map_err(|e| OfframpError::Schema(e.to_string()))?;
+    std::fs::write(path, raw_data)?;
+
+    Ok(())
+}
+
+/// Reads the data written by `save_aligned_verification_data`.
+pub fn load_aligned_verification_data(
+    path: &Path,
+) -> Result<AlignedVerificationData, OfframpError> {
+    let raw_data = std::fs::read_to_string(path)?;
+    serde_json::from_str(&raw_data).map_err(|error| {
+        OfframpError::Input(format!(
+            "invalid Aligned verification data file {}: {}",
+            path.display(),
+            error
+        ))
+    })
+}
+
+/// Writes the fixture of a PLONK wrapped proof, to check it against the SP1
+/// verifier contracts.
+pub fn save_plonk_fixture(path: &Path, fixture: &PlonkFixture) -> Result<(), OfframpError> {
+    let raw_fixture =
+        serde_json::to_string_pretty(fixture).map_err(|e| OfframpError::Schema(e.to_string()))?;
+    std::fs::write(path, raw_fixture)?;
+
+    Ok(())
+}
+
+/// Reads a fixture written by `save_plonk_fixture`.
+pub fn load_plonk_fixture(path: &Path) -> Result<PlonkFixture, OfframpError> {
+    let raw_fixture = std::fs::read_to_string(path)?;
+    serde_json::from_str(&raw_fixture).map_err(|error| {
+        OfframpError::Input(format!(
+            "invalid PLONK fixture file {}: {}",
+            path.display(),
+            error
+        ))
+    })
+}
@@ -0,0 +1,178 @@
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use reqwest::{Certificate, StatusCode, Url};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::error::OfframpError;
+use crate::types;
+
+/// Attestor used when none is configured.
+pub const DEFAULT_ATTESTOR_URL: &str = "https://mock.blocknaut.xyz";
+
+/// Where and how to reach the zkTLS attestor. Every value can be swapped
+/// from the command line or the environment to target staging, production or
+/// a local mock.
+#[derive(clap::Args, Debug, Clone)]
+pub struct AttestorArgs {
+    /// Base URL of the zkTLS attestor.
+    #[arg(long, env = "JACKRAMP_ATTESTOR_URL", default_value = DEFAULT_ATTESTOR_URL)]
+    pub attestor_url: String,
+    /// Path of the transfer proof endpoint, relative to the base URL.
+    #[arg(
+        long,
+        env = "JACKRAMP_ATTESTOR_PROOF_PATH",
+        default_value = "generateTransferProof"
+    )]
+    pub attestor_proof_path: String,
+    /// Authorization header value, with `{token}` replaced by the token.
+    #[arg(
+        long,
+        env = "JACKRAMP_ATTESTOR_AUTH_FORMAT",
+        default_value = "Bearer {token}"
+    )]
+    pub attestor_auth_format: String,
+    /// Extra `Name: value` header sent with every request.
+    #[arg(
+        long = "attestor-header",
+        env = "JACKRAMP_ATTESTOR_HEADERS",
+        value_delimiter = ','
+    )]
+    pub attestor_headers: Vec<String>,
+    /// PEM file holding extra root certificates to trust.
+    #[arg(long, env = "JACKRAMP_ATTESTOR_CA_CERT")]
+    pub attestor_ca_cert: Option<PathBuf>,
+    /// Seconds to wait for a single request.
+    #[arg(long, env = "JACKRAMP_ATTESTOR_TIMEOUT", default_value_t = 120)]
+    pub attestor_timeout: u64,
+    /// How many times a failed request is retried.
+    #[arg(long, env = "JACKRAMP_ATTESTOR_RETRIES", default_value_t = 3)]
+    pub attestor_retries: u32,
+    /// Milliseconds before the first retry, doubled after every attempt.
+    #[arg(long, env = "JACKRAMP_ATTESTOR_BACKOFF_MS", default_value_t = 500)]
+    pub attestor_backoff_ms: u64,
+}
+
+/// Requests Reclaim proofs of bank transfers from a zkTLS attestor.
+#[derive(Debug, Clone)]
+pub struct AttestorClient {
+    client: reqwest::Client,
+    proof_url: Url,
+    auth_format: String,
+    retries: u32,
+    backoff: Duration,
+}
+
+impl AttestorClient {
+    pub fn new(args: &AttestorArgs) -> Result<Self, OfframpError> {
+        let invalid = |error: String| OfframpError::Input(format!("invalid attestor {}", error));
+
+        let mut base_url = Url::parse(&args.attestor_url)
+            .map_err(|e| invalid(format!("url {}: {}", args.attestor_url, e)))?;
+        // Keep the last path segment of the base URL when joining.
+        if !base_url.path().ends_with('/') {
+            base_url.set_path(&format!("{}/", base_url.path()));
+        }
+        let proof_url = base_url
+            .join(args.attestor_proof_path.trim_start_matches('/'))
+            .map_err(|e| invalid(format!("path {}: {}", args.attestor_proof_path, e)))?;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        for header in &args.attestor_headers {
+            let (name, value) = header
+                .split_once(':')
+                .ok_or_else(|| invalid(format!("header {}: expected `Name: value`", header)))?;
+            let name = HeaderName::from_bytes(name.trim().as_bytes())
+                .map_err(|e| invalid(format!("header {}: {}", header, e)))?;
+            let value = HeaderValue::from_str(value.trim())
+                .map_err(|e| invalid(format!("header {}: {}", header, e)))?;
+            headers.insert(name, value);
+        }
+
+        let mut builder = reqwest::Client::builder()
+            .default_headers(headers)
+            .timeout(Duration::from_secs(args.attestor_timeout));
+
+        if let Some(path) = &args.attestor_ca_cert {
+            let pem = std::fs::read(path)?;
+            let certificate = Certificate::from_pem(&pem)
+                .map_err(|e| invalid(format!("certificate {}: {}", path.display(), e)))?;
+            builder = builder.add_root_certificate(certificate);
+        }
+
+        Ok(AttestorClient {
+            client: builder.build()?,
+            proof_url,
+            auth_format: args.attestor_auth_format.clone(),
+            retries: args.attestor_retries,
+            backoff: Duration::from_millis(args.attestor_backoff_ms),
+        })
+    }
+
+    /// Asks the attestor for a Reclaim proof of the bank transfer in
+    /// `request_body`. Network errors, rate limiting and server errors are
+    /// retried with exponential backoff; other failures are returned at once.
+    pub async fn fetch_transfer_proof(
+        &self,
+        request_body: &types::TransferRequest,
+        authorization: &str,
+    ) -> Result<types::Proof, OfframpError> {
+        let mut backoff = self.backoff;
+        let mut attempt = 0;
+
+        loop {
+            match self
+                .request_transfer_proof(request_body, authorization)
+                .await
+            {
+                Err(error) if attempt < self.retries && is_transient(&error) => {
+                    log::warn!(
+                        "attestor request failed, retrying in {:?}: {}",
+                        backoff,
+                        error
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn request_transfer_proof(
+        &self,
+        request_body: &types::TransferRequest,
+        authorization: &str,
+    ) -> Result<types::Proof, OfframpError> {
+        let response = self
+            .client
+            .post(self.proof_url.clone())
+            .header(
+                AUTHORIZATION,
+                self.auth_format.replace("{token}", authorization),
+            )
+            .json(request_body)
+            .send()
+            .await?;
+
+        match response.status() {
+            StatusCode::OK => response
+                .json::<types::Proof>()
+                .await
+                .map_err(|error| OfframpError::Schema(error.to_string())),
+            StatusCode::UNAUTHORIZED => Err(OfframpError::Unauthorized),
+            other => Err(OfframpError::UnexpectedStatus(other)),
+        }
+    }
+}
+
+fn is_transient(error: &OfframpError) -> bool {
+    match error {
+        OfframpError::Fetch(_) => true,
+        OfframpError::UnexpectedStatus(status) => {
+            status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+        }
+        _ => false,
+    }
+}
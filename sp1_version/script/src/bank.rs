@@ -0,0 +1,123 @@
+use ethers::types::H256;
+use zktransfer_lib::banks::{self, BankResponse, BcaResponse, BniResponse, MandiriResponse};
+
+use crate::error::OfframpError;
+use crate::types;
+
+/// Host side knowledge of a bank channel. The zkVM program parses the same
+/// response shapes, picking the parser by `hashedChannelId`.
+pub trait BankProvider {
+    /// Channel id as entered by users, e.g. `bca`.
+    fn channel_id(&self) -> &'static str;
+
+    /// Maps a bank API response onto canonical transactions.
+    fn parse_response(&self, response: &str) -> Result<Vec<types::Transaction>, OfframpError>;
+
+    /// The `hashedChannelId` committed for transfers on this channel.
+    fn hashed_channel_id(&self) -> H256 {
+        H256(banks::hashed_channel_id(self.channel_id()))
+    }
+
+    /// Body asking the attestor to prove `transaction_id` for `address`.
+    fn transfer_request(&self, transaction_id: &str, address: &str) -> types::TransferRequest {
+        types::TransferRequest {
+            id: String::from(transaction_id),
+            bank: String::from(self.channel_id()),
+            address: String::from(address),
+        }
+    }
+
+    /// Finds `transaction_id` in the responses attested by a Reclaim proof,
+    /// so a proof of the wrong transfer fails before proving.
+    fn find_transaction(
+        &self,
+        proof: &types::Proof,
+        transaction_id: &str,
+    ) -> Result<types::Transaction, OfframpError> {
+        let parameters: types::Parameters = serde_json::from_str(&proof.claim_info.parameters)
+            .map_err(|e| OfframpError::Schema(format!("claimInfo.parameters: {}", e)))?;
+
+        for response_match in parameters
+            .response_matches
+            .iter()
+            .filter(|response_match| response_match.is_attested())
+        {
+            let found = self
+                .parse_response(&response_match.value_resp)?
+                .into_iter()
+                .find(|transaction| {
+                    transaction.id == transaction_id
+                        && banks::normalize_channel_id(&transaction.bank) == self.channel_id()
+                });
+
+            if let Some(transaction) = found {
+                return Ok(transaction);
+            }
+        }
+
+        Err(OfframpError::Schema(format!(
+            "the Reclaim proof does not contain transaction {} on {}",
+            transaction_id,
+            self.channel_id()
+        )))
+    }
+}
+
+fn parse<R: BankResponse>(
+    channel_id: &str,
+    response: &str,
+) -> Result<Vec<types::Transaction>, OfframpError> {
+    serde_json::from_str::<R>(response)
+        .map(|response| response.into_transactions(channel_id))
+        .map_err(|e| OfframpError::Schema(format!("{} response: {}", channel_id, e)))
+}
+
+pub struct Bca;
+
+impl BankProvider for Bca {
+    fn channel_id(&self) -> &'static str {
+        banks::BCA
+    }
+
+    fn parse_response(&self, response: &str) -> Result<Vec<types::Transaction>, OfframpError> {
+        parse::<BcaResponse>(self.channel_id(), response)
+    }
+}
+
+pub struct Mandiri;
+
+impl BankProvider for Mandiri {
+    fn channel_id(&self) -> &'static str {
+        banks::MANDIRI
+    }
+
+    fn parse_response(&self, response: &str) -> Result<Vec<types::Transaction>, OfframpError> {
+        parse::<MandiriResponse>(self.channel_id(), response)
+    }
+}
+
+pub struct Bni;
+
+impl BankProvider for Bni {
+    fn channel_id(&self) -> &'static str {
+        banks::BNI
+    }
+
+    fn parse_response(&self, response: &str) -> Result<Vec<types::Transaction>, OfframpError> {
+        parse::<BniResponse>(self.channel_id(), response)
+    }
+}
+
+/// The provider of `channel_id`, ignoring case.
+pub fn bank_provider(channel_id: &str) -> Result<Box<dyn BankProvider>, OfframpError> {
+    match banks::normalize_channel_id(channel_id).as_str() {
+        banks::BCA => Ok(Box::new(Bca)),
+        banks::MANDIRI => Ok(Box::new(Mandiri)),
+        banks::BNI => Ok(Box::new(Bni)),
+        _ => Err(OfframpError::Input(format!(
+            "unknown channel id {}, expected one of {}",
+            channel_id,
+            banks::CHANNEL_IDS.join(", ")
+        ))),
+    }
+}
@@ -1,21 +1,30 @@
-use script::types;
-
-use aligned_sdk::core::types::{
-    AlignedVerificationData, Network, PriceEstimate, ProvingSystemId, VerificationData,
+use script::artifact::{
+    load_aligned_verification_data, load_reclaim_proof, load_sp1_proof,
+    save_aligned_verification_data, save_plonk_fixture, save_reclaim_proof, save_sp1_proof,
+};
+use script::attestor::{AttestorArgs, AttestorClient};
+use script::bank::bank_provider;
+use script::config::{Answers, ProgramArgs, PromptArgs};
+use script::contract::{JackRampContract, OfframpRequestParams, SP1VerifierGateway};
+use script::evm::{claim, connect, deposit, preflight, proof_hash, submit, WalletArgs};
+use script::onchain::{
+    claim_onchain, plonk_fixture, plonk_proof_bytes, program_vkey, verify_onchain, Settlement,
+};
+use script::status::OfframpRequestState;
+use script::{
+    build_stdin, is_mock_proof, load_rate_quote, price, prover_client, request_offramp_id, types,
+    OfframpError,
 };
-use aligned_sdk::sdk::{deposit_to_aligned, estimate_fee};
-use aligned_sdk::sdk::{get_next_nonce, submit_and_wait_verification};
-use clap::Parser;
-use dialoguer::Confirm;
-use ethers::prelude::*;
-use ethers::providers::{Http, Provider};
-use ethers::signers::{LocalWallet, Signer};
-use ethers::types::{Address, Bytes, H160, U256};
-use reqwest;
-use sp1_sdk::{ProverClient, SP1ProofWithPublicValues, SP1Stdin};
-use std::io;
 
-abigen!(JackRampContract, "JackRampContract.json",);
+use clap::{Parser, Subcommand};
+use ethers::providers::{Http, Provider};
+use ethers::types::{Bytes, H160, H256, U256};
+use ethers::utils::{format_units, keccak256, parse_units};
+use sp1_sdk::{ProverClient, SP1ProofWithPublicValues};
+use std::path::PathBuf;
+use std::sync::Arc;
+use zktransfer_lib::verify::reclaim::hash_claim_info;
+use zktransfer_lib::Amount;
 
 /// The ELF (executable and linkable format) file for the Succinct RISC-V zkVM.
 pub const ZKTRANSFER_ELF: &[u8] =
@@ -23,269 +32,841 @@ pub const ZKTRANSFER_ELF: &[u8] =
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
-struct Args {
-    #[arg(short, long)]
-    keystore_path: String,
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Deposit 0.004eth to Aligned to pay for proof verification.
+    Deposit(DepositArgs),
+    /// Fetch a Reclaim proof for each bank transfer.
+    Fetch(FetchArgs),
+    /// Prove saved Reclaim proofs with the zkVM program.
+    Prove(ProveArgs),
+    /// Verify a saved SP1 proof locally.
+    Verify(VerifyArgs),
+    /// Submit a saved SP1 proof to Aligned and wait for its verification.
+    Submit(SubmitArgs),
+    /// Claim stablecoins with a proof already verified by Aligned.
+    Claim(ClaimArgs),
+    /// Run every step, keeping the artifacts so a failed step can be retried.
+    Run(Box<RunArgs>),
+    /// Lock stablecoins in an offramp request, to be paid out on a bank account.
+    RequestOfframp(RequestOfframpArgs),
+    /// Show an offramp request as stored by the contract.
+    RequestStatus(RequestStatusArgs),
+    /// Check whether a Reclaim proof or an Aligned proof was already used to fill a request.
+    ProofStatus(ProofStatusArgs),
+    /// Show the stablecoin balance of an address.
+    Balance(BalanceArgs),
+    /// Burn stablecoins to withdraw the underlying USD.
+    Withdraw(WithdrawArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct DepositArgs {
+    #[command(flatten)]
+    wallet: WalletArgs,
+    #[command(flatten)]
+    prompts: PromptArgs,
+}
+
+#[derive(clap::Args, Debug)]
+struct FetchArgs {
+    /// Reclaim proof file to write, one per bank transfer.
+    #[arg(long = "reclaim-proof", required = true)]
+    reclaim_proofs: Vec<PathBuf>,
+    #[command(flatten)]
+    attestor: AttestorArgs,
+    #[command(flatten)]
+    prompts: PromptArgs,
+}
+
+#[derive(clap::Args, Debug)]
+struct ProveArgs {
+    /// Reclaim proof file written by `fetch`, one per bank transfer.
+    #[arg(long = "reclaim-proof", required = true)]
+    reclaim_proofs: Vec<PathBuf>,
+    /// Where to write the bincode SP1 proof.
+    #[arg(long, default_value = "proof.bin")]
+    sp1_proof: PathBuf,
+    /// `plonk` wraps the proof for the SP1 verifier gateway, which takes
+    /// much longer to prove.
+    #[arg(long, value_enum, default_value_t)]
+    settlement: Settlement,
+    /// Also write the PLONK wrapped proof as a fixture for the SP1 verifier
+    /// contracts, as `tests/anvil.rs` reads it.
+    #[arg(long)]
+    plonk_fixture: Option<PathBuf>,
+    #[command(flatten)]
+    program: ProgramArgs,
+    #[command(flatten)]
+    prompts: PromptArgs,
+}
+
+#[derive(clap::Args, Debug)]
+struct VerifyArgs {
+    /// SP1 proof written by `prove`.
+    #[arg(long, default_value = "proof.bin")]
+    sp1_proof: PathBuf,
+    /// Also verify a PLONK wrapped proof with an `eth_call` to this SP1
+    /// verifier gateway.
+    #[arg(long)]
+    verifier_address: Option<H160>,
     #[arg(
         short,
         long,
         default_value = "https://ethereum-holesky-rpc.publicnode.com"
     )]
     rpc_url: String,
+    /// Accept a mock proof written by `prove --mock`, which has nothing to verify.
+    #[arg(long, env = "JACKRAMP_MOCK_PROVER")]
+    mock: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct SubmitArgs {
+    #[command(flatten)]
+    wallet: WalletArgs,
+    #[arg(short, long, default_value = "wss://batcher.alignedlayer.com")]
+    batcher_url: String,
+    /// Checked before submitting, so the proof is not verified for a request it can't fill.
+    #[arg(short, long)]
+    jackramp_contract_address: H160,
+    /// Highest Aligned fee, in ETH, accepted without asking.
+    #[arg(long, env = "JACKRAMP_MAX_FEE")]
+    max_fee: Option<String>,
+    /// SP1 proof written by `prove`.
+    #[arg(long, default_value = "proof.bin")]
+    sp1_proof: PathBuf,
+    /// Where to write the Aligned verification data.
+    #[arg(long, default_value = "aligned_verification_data.json")]
+    aligned_data: PathBuf,
+    /// Stub the Aligned verification of a mock proof written by `prove --mock`.
+    #[arg(long, env = "JACKRAMP_MOCK_PROVER")]
+    mock: bool,
+    #[command(flatten)]
+    prompts: PromptArgs,
+}
+
+#[derive(clap::Args, Debug)]
+struct ClaimArgs {
+    #[command(flatten)]
+    wallet: WalletArgs,
+    #[arg(short, long)]
+    jackramp_contract_address: H160,
+    /// SP1 proof written by `prove`.
+    #[arg(long, default_value = "proof.bin")]
+    sp1_proof: PathBuf,
+    /// Aligned verification data written by `submit`.
+    #[arg(long, default_value = "aligned_verification_data.json")]
+    aligned_data: PathBuf,
+    /// `plonk` claims a PLONK wrapped proof through the gateway variant of the
+    /// contract, without Aligned verification data.
+    #[arg(long, value_enum, default_value_t)]
+    settlement: Settlement,
+    /// Claim batch proofs through `fillOfframpBatch`, or
+    /// `fillOfframpBatchWithSp1Proof` with `--settlement plonk`. The deployed
+    /// `JackRampContract` does not have them, only a contract built with batch
+    /// settlement does.
+    #[arg(long)]
+    batch_settlement: bool,
+    /// Claim a mock proof with the verification data `submit --mock` stubbed,
+    /// which only a contract on top of a stubbed Aligned service manager accepts.
+    #[arg(long, env = "JACKRAMP_MOCK_PROVER")]
+    mock: bool,
+    #[command(flatten)]
+    prompts: PromptArgs,
+}
+
+#[derive(clap::Args, Debug)]
+struct RunArgs {
+    #[command(flatten)]
+    wallet: WalletArgs,
+    #[command(flatten)]
+    program: ProgramArgs,
     #[arg(short, long, default_value = "wss://batcher.alignedlayer.com")]
     batcher_url: String,
-    #[arg(short, long, default_value = "holesky")]
-    network: Network,
     #[arg(short, long)]
     jackramp_contract_address: H160,
+    /// Number of bank transfers to prove and claim with a single proof.
+    /// Defaults to the number of transfers in the config file. More than one
+    /// needs `--batch-settlement`.
+    #[arg(long)]
+    batch_size: Option<usize>,
+    /// Claim batch proofs through `fillOfframpBatch`, or
+    /// `fillOfframpBatchWithSp1Proof` with `--settlement plonk`. The deployed
+    /// `JackRampContract` does not have them, only a contract built with batch
+    /// settlement does.
+    #[arg(long)]
+    batch_settlement: bool,
+    /// Deposit to Aligned without asking when running with `--yes`.
+    #[arg(long)]
+    deposit: bool,
+    /// Highest Aligned fee, in ETH, accepted without asking.
+    #[arg(long, env = "JACKRAMP_MAX_FEE")]
+    max_fee: Option<String>,
+    /// Directory holding the Reclaim proofs, SP1 proof and Aligned verification data.
+    #[arg(long, default_value = "artifacts")]
+    artifacts_dir: PathBuf,
+    /// `plonk` verifies the proof in the claim transaction through the SP1
+    /// verifier gateway, `--jackramp-contract-address` being the gateway
+    /// variant of the contract.
+    #[arg(long, value_enum, default_value_t)]
+    settlement: Settlement,
+    #[command(flatten)]
+    attestor: AttestorArgs,
+    #[command(flatten)]
+    prompts: PromptArgs,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(group(clap::ArgGroup::new("pricing").required(true).args(["amount", "rate_quote"])))]
+struct RequestOfframpArgs {
+    #[command(flatten)]
+    wallet: WalletArgs,
+    #[arg(short, long)]
+    jackramp_contract_address: H160,
+    /// Bank account to be paid on, as the bank reports it, e.g. `111111`.
+    /// The bank is picked with `--channel-id`.
+    #[arg(long)]
+    channel_account: String,
+    /// Real world amount to be paid, e.g. `1000000` or `1.000.000,00`.
+    #[arg(long)]
+    amount_real_world: Amount,
+    /// Stablecoins to lock, e.g. `64.5`.
+    #[arg(long)]
+    amount: Option<String>,
+    /// Price `--amount-real-world` with this signed rate quote, the way the
+    /// program will, instead of passing `--amount`.
+    #[arg(long)]
+    rate_quote: Option<String>,
+    #[command(flatten)]
+    prompts: PromptArgs,
+}
+
+/// Read-only access to `JackRampContract`, no keystore needed.
+#[derive(clap::Args, Debug)]
+struct ReadArgs {
+    #[arg(
+        short,
+        long,
+        default_value = "https://ethereum-holesky-rpc.publicnode.com"
+    )]
+    rpc_url: String,
+    #[arg(short, long)]
+    jackramp_contract_address: H160,
+    /// Print JSON instead of text.
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct RequestStatusArgs {
+    #[command(flatten)]
+    read: ReadArgs,
+    /// The id printed by `request-offramp` and `main --execute`.
+    #[arg(long)]
+    request_offramp_id: H256,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(group(
+    clap::ArgGroup::new("proofs")
+        .required(true)
+        .multiple(true)
+        .args(["reclaim_proof", "hashed_claim_info", "aligned_data", "proof_hash"])
+))]
+struct ProofStatusArgs {
+    #[command(flatten)]
+    read: ReadArgs,
+    /// Reclaim proof file, checked against `usedReclaimProofs`.
+    #[arg(long)]
+    reclaim_proof: Option<PathBuf>,
+    /// `hashedClaimInfo` of a Reclaim proof, checked against `usedReclaimProofs`.
+    #[arg(long)]
+    hashed_claim_info: Option<H256>,
+    /// Aligned verification data written by `submit`, checked against `usedProofs`.
+    #[arg(long)]
+    aligned_data: Option<PathBuf>,
+    /// Hash of an Aligned verification data commitment, checked against `usedProofs`.
+    #[arg(long)]
+    proof_hash: Option<H256>,
+}
+
+#[derive(clap::Args, Debug)]
+struct BalanceArgs {
+    #[command(flatten)]
+    read: ReadArgs,
+    #[arg(long)]
+    address: H160,
+}
+
+#[derive(clap::Args, Debug)]
+struct WithdrawArgs {
+    #[command(flatten)]
+    wallet: WalletArgs,
+    #[arg(short, long)]
+    jackramp_contract_address: H160,
+    /// Stablecoins to burn, e.g. `64.5`.
+    #[arg(long)]
+    amount: String,
+    /// Print JSON instead of text.
+    #[arg(long)]
+    json: bool,
+    #[command(flatten)]
+    prompts: PromptArgs,
 }
 
 #[tokio::main]
 async fn main() {
     sp1_sdk::utils::setup_logger();
 
-    let args = Args::parse();
-    let rpc_url = args.rpc_url.clone();
+    if let Err(error) = run(Cli::parse().command).await {
+        eprintln!("Error: {}", error);
+        std::process::exit(1);
+    }
+}
 
-    let keystore_password = rpassword::prompt_password("Enter keystore password: ")
-        .expect("Failed to read keystore password");
+async fn run(command: Command) -> Result<(), OfframpError> {
+    match command {
+        Command::Deposit(args) => {
+            let answers = Answers::load(args.prompts)?;
+            let signer = connect(&args.wallet, &answers).await?;
+            deposit(signer, args.wallet.network).await
+        }
+        Command::Fetch(args) => {
+            let answers = Answers::load(args.prompts)?;
+            let attestor = AttestorClient::new(&args.attestor)?;
+            fetch(&attestor, &answers, &args.reclaim_proofs).await
+        }
+        Command::Prove(args) => {
+            let answers = Answers::load(args.prompts)?;
+            let proof = prove(
+                &args.program,
+                &answers,
+                &args.reclaim_proofs,
+                args.settlement,
+            )?;
+            save_sp1_proof(&args.sp1_proof, &proof)?;
+            println!("SP1 proof saved to {}", args.sp1_proof.display());
+
+            if let Some(path) = args.plonk_fixture {
+                let (_, vk) = ProverClient::new().setup(ZKTRANSFER_ELF);
+                save_plonk_fixture(&path, &plonk_fixture(&proof, &vk)?)?;
+                println!("PLONK fixture saved to {}", path.display());
+            }
+            Ok(())
+        }
+        Command::Verify(args) => {
+            let proof = load_sp1_proof(&args.sp1_proof)?;
+            verify(&proof, args.mock)?;
+
+            if let Some(verifier_address) = args.verifier_address {
+                let provider = Provider::<Http>::try_from(args.rpc_url.as_str())
+                    .map_err(|e| OfframpError::Rpc(e.to_string()))?;
+                let (_, vk) = ProverClient::new().setup(ZKTRANSFER_ELF);
+
+                verify_onchain(
+                    &SP1VerifierGateway::new(verifier_address, Arc::new(provider)),
+                    program_vkey(&vk)?,
+                    Bytes::from(proof.public_values.to_vec()),
+                    plonk_proof_bytes(&proof)?,
+                )
+                .await?;
+                println!("Successfully verified proof on chain!");
+            }
+            Ok(())
+        }
+        Command::Submit(args) => {
+            let answers = Answers::load(args.prompts)?;
+            let max_fee_ceiling = answers.max_fee(args.max_fee.as_ref())?;
+            let signer = connect(&args.wallet, &answers).await?;
+            let proof = load_sp1_proof(&args.sp1_proof)?;
+            preflight(
+                &JackRampContract::new(args.jackramp_contract_address, Arc::new(signer.clone())),
+                &proof,
+            )
+            .await?;
+
+            if let Some(aligned_verification_data) = submit(
+                &signer,
+                &answers,
+                &args.wallet,
+                &args.batcher_url,
+                max_fee_ceiling,
+                ZKTRANSFER_ELF,
+                &proof,
+                args.mock,
+            )
+            .await?
+            {
+                save_aligned_verification_data(&args.aligned_data, &aligned_verification_data)?;
+                println!(
+                    "Aligned verification data saved to {}",
+                    args.aligned_data.display()
+                );
+            }
+            Ok(())
+        }
+        Command::Claim(args) => {
+            let answers = Answers::load(args.prompts)?;
+            let signer = connect(&args.wallet, &answers).await?;
+            let proof = load_sp1_proof(&args.sp1_proof)?;
+            if args.settlement == Settlement::Plonk {
+                claim_onchain(
+                    signer,
+                    &args.jackramp_contract_address,
+                    &proof,
+                    args.batch_settlement,
+                )
+                .await?;
+                return Ok(());
+            }
+            let aligned_verification_data = load_aligned_verification_data(&args.aligned_data)?;
+
+            claim(
+                &aligned_verification_data,
+                signer,
+                &args.jackramp_contract_address,
+                &proof,
+                args.batch_settlement,
+                args.mock,
+            )
+            .await?;
+            Ok(())
+        }
+        Command::Run(args) => run_all(*args).await,
+        Command::RequestOfframp(args) => request_offramp(args).await,
+        Command::RequestStatus(args) => request_status(args).await,
+        Command::ProofStatus(args) => proof_status(args).await,
+        Command::Balance(args) => balance(args).await,
+        Command::Withdraw(args) => withdraw(args).await,
+    }
+}
+
+async fn run_all(args: RunArgs) -> Result<(), OfframpError> {
+    let answers = Answers::load(args.prompts)?;
+    let attestor = AttestorClient::new(&args.attestor)?;
+    let max_fee_ceiling = answers.max_fee(args.max_fee.as_ref())?;
+    let batch_size = args.batch_size.unwrap_or(answers.transfer_count());
+    if batch_size > 1 && !args.batch_settlement {
+        return Err(OfframpError::Input(format!(
+            "{} transfers make a batch proof, which only a contract with fillOfframpBatch settles: pass --batch-settlement, or --batch-size 1",
+            batch_size
+        )));
+    }
+    let signer = connect(&args.wallet, &answers).await?;
+
+    if args.settlement == Settlement::Aligned
+        && answers.confirm_deposit(
+            args.deposit,
+            "Do you want to deposit 0.004eth in Aligned ?\nIf you already deposited Ethereum to Aligned before, this is not needed",
+        )?
+    {
+        deposit(signer.clone(), args.wallet.network).await?;
+    }
 
-    let provider =
-        Provider::<Http>::try_from(rpc_url.as_str()).expect("Failed to connect to provider");
+    std::fs::create_dir_all(&args.artifacts_dir)?;
+    let reclaim_proofs: Vec<PathBuf> = (0..batch_size)
+        .map(|index| {
+            args.artifacts_dir
+                .join(format!("reclaim_proof_{}.json", index))
+        })
+        .collect();
+    let sp1_proof_path = args.artifacts_dir.join("proof.bin");
+    let aligned_data_path = args.artifacts_dir.join("aligned_verification_data.json");
 
-    let chain_id = provider
-        .get_chainid()
-        .await
-        .expect("Failed to get chain_id");
+    fetch(&attestor, &answers, &reclaim_proofs).await?;
 
-    let wallet = LocalWallet::decrypt_keystore(args.keystore_path, &keystore_password)
-        .expect("Failed to decrypt keystore")
-        .with_chain_id(chain_id.as_u64());
+    let proof = prove(&args.program, &answers, &reclaim_proofs, args.settlement)?;
+    save_sp1_proof(&sp1_proof_path, &proof)?;
+    verify(&proof, args.program.mock)?;
+    preflight(
+        &JackRampContract::new(args.jackramp_contract_address, Arc::new(signer.clone())),
+        &proof,
+    )
+    .await?;
+
+    if args.settlement == Settlement::Plonk {
+        claim_onchain(
+            signer,
+            &args.jackramp_contract_address,
+            &proof,
+            args.batch_settlement,
+        )
+        .await?;
+        return Ok(());
+    }
 
-    let signer = SignerMiddleware::new(provider.clone(), wallet.clone());
+    let Some(aligned_verification_data) = submit(
+        &signer,
+        &answers,
+        &args.wallet,
+        &args.batcher_url,
+        max_fee_ceiling,
+        ZKTRANSFER_ELF,
+        &proof,
+        args.program.mock,
+    )
+    .await?
+    else {
+        return Ok(());
+    };
+    save_aligned_verification_data(&aligned_data_path, &aligned_verification_data)?;
 
-    if Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
-        .with_prompt("Do you want to deposit 0.004eth in Aligned ?\nIf you already deposited Ethereum to Aligned before, this is not needed")
-        .interact()
-        .expect("Failed to read user input") {   
+    claim(
+        &aligned_verification_data,
+        signer,
+        &args.jackramp_contract_address,
+        &proof,
+        args.batch_settlement,
+        args.program.mock,
+    )
+    .await?;
+    Ok(())
+}
 
-        deposit_to_aligned(U256::from(4000000000000000u128), signer.clone(), args.network).await
-        .expect("Failed to pay for proof submission");
+async fn request_offramp(args: RequestOfframpArgs) -> Result<(), OfframpError> {
+    let answers = Answers::load(args.prompts)?;
+    let channel_id = answers.channel_id(0)?;
+    let bank = bank_provider(&channel_id)?;
+    let signer = connect(&args.wallet, &answers).await?;
+    let user = signer.address();
+    let jr_contract = JackRampContract::new(args.jackramp_contract_address, Arc::new(signer));
+
+    let decimals = jr_contract
+        .decimals()
+        .call()
+        .await
+        .map_err(OfframpError::from_contract_error)?;
+    let amount: U256 = match (&args.amount, &args.rate_quote) {
+        (Some(amount), _) => parse_units(amount, u32::from(decimals))
+            .map_err(|e| OfframpError::Input(format!("invalid amount {}: {}", amount, e)))?
+            .into(),
+        (None, Some(rate_quote)) => price(args.amount_real_world, &load_rate_quote(rate_quote)?)?,
+        (None, None) => {
+            return Err(OfframpError::Input(String::from(
+                "either --amount or --rate-quote is required",
+            )))
+        }
+    };
+
+    let params = OfframpRequestParams {
+        user,
+        amount,
+        amount_real_world: U256::from(args.amount_real_world.minor_units()),
+        channel_account: keccak256(&args.channel_account),
+        channel_id: bank.hashed_channel_id().0,
+    };
+    let request_id = request_offramp_id(&(&params).into());
+
+    let amount_string = format_units(amount, u32::from(decimals))
+        .map_err(|e| OfframpError::Input(e.to_string()))?;
+    if !answers.confirm(&format!(
+        "Lock {} stablecoins to be paid {} on {} account {}?",
+        amount_string, args.amount_real_world, channel_id, args.channel_account
+    ))? {
+        return Ok(());
     }
 
-    println!("Welcome to the zkTransfer! Input the bank transaction, generate a zkProof, and claim your USD!");
-    println!("You will be asked to input Bank, Transaction ID, sender address, and Authorization token.");
+    let allowance = jr_contract
+        .allowance(user, jr_contract.address())
+        .call()
+        .await
+        .map_err(OfframpError::from_contract_error)?;
+    if allowance < amount {
+        jr_contract
+            .approve(jr_contract.address(), amount)
+            .send()
+            .await
+            .map_err(OfframpError::from_contract_error)?
+            .await
+            .map_err(|e| OfframpError::Contract(format!("Failed to approve {}", e)))?;
+        println!("Approved {} stablecoins", amount_string);
+    }
 
-    println!("{}", "What is your Channel ID?");
-    let mut channel_id_answer = String::new();
+    let receipt = jr_contract
+        .request_offramp(params)
+        .send()
+        .await
+        .map_err(OfframpError::from_contract_error)?
+        .await
+        .map_err(|e| OfframpError::Contract(format!("Failed to submit tx {}", e)))?
+        .ok_or_else(|| {
+            OfframpError::Contract(String::from("Failed to request offramp: no receipt"))
+        })?;
 
-    io::stdin()
-        .read_line(&mut channel_id_answer)
-        .expect("Failed to read from stdin");
+    println!(
+        "Offramp requested. Transaction hash: {:x}",
+        receipt.transaction_hash
+    );
+    println!("requestOfframpId: 0x{:x}", request_id);
+    Ok(())
+}
 
-    println!("{}", "Input your Transaction ID?");
-    let mut trx_id_answer = String::new();
+fn read_contract(read_args: &ReadArgs) -> Result<JackRampContract<Provider<Http>>, OfframpError> {
+    let provider = Provider::<Http>::try_from(read_args.rpc_url.as_str())
+        .map_err(|e| OfframpError::Rpc(e.to_string()))?;
 
-    io::stdin()
-        .read_line(&mut trx_id_answer)
-        .expect("Failed to read from stdin");
+    Ok(JackRampContract::new(
+        read_args.jackramp_contract_address,
+        Arc::new(provider),
+    ))
+}
 
-    println!("{}", "Input your Sender Address?");
-    let mut sender_address_answer = String::new();
+async fn request_status(args: RequestStatusArgs) -> Result<(), OfframpError> {
+    let jr_contract = read_contract(&args.read)?;
+    let state = OfframpRequestState::fetch(&jr_contract, args.request_offramp_id).await?;
 
-    io::stdin()
-        .read_line(&mut sender_address_answer)
-        .expect("Failed to read from stdin");
+    if args.read.json {
+        println!("{}", state.to_json());
+    } else {
+        println!("{}", state);
+    }
+    Ok(())
+}
 
-    println!("{}", "Input your Authorization Token?");
-    let mut trx_authorization_answer = String::new();
+async fn proof_status(args: ProofStatusArgs) -> Result<(), OfframpError> {
+    let jr_contract = read_contract(&args.read)?;
 
-    io::stdin()
-        .read_line(&mut trx_authorization_answer)
-        .expect("Failed to read from stdin");
+    let mut hashed_claim_infos: Vec<H256> = args.hashed_claim_info.into_iter().collect();
+    if let Some(path) = &args.reclaim_proof {
+        hashed_claim_infos.push(H256(
+            hash_claim_info(&load_reclaim_proof(path)?.claim_info).0,
+        ));
+    }
 
-    println!(
-        "Answers {}:{}:{}:{}",
-        &channel_id_answer.trim(),
-        &trx_id_answer.trim(),
-        &sender_address_answer.trim(),
-        &trx_authorization_answer.trim()
-    );
+    let mut proof_hashes: Vec<H256> = args.proof_hash.into_iter().collect();
+    if let Some(path) = &args.aligned_data {
+        proof_hashes.push(H256(proof_hash(&load_aligned_verification_data(path)?)));
+    }
 
-    let client = reqwest::Client::new();
+    let mut reclaim_proofs = Vec::new();
+    for hash in hashed_claim_infos {
+        let used = jr_contract
+            .used_reclaim_proofs(hash.0)
+            .call()
+            .await
+            .map_err(OfframpError::from_contract_error)?;
+        reclaim_proofs.push((hash, used));
+    }
 
-    let request_body = types::TransferRequest {
-        id: String::from(trx_id_answer.trim()),
-        bank: String::from(channel_id_answer.trim()),
-    };
+    let mut proofs = Vec::new();
+    for hash in proof_hashes {
+        let used = jr_contract
+            .used_proofs(hash.0)
+            .call()
+            .await
+            .map_err(OfframpError::from_contract_error)?;
+        proofs.push((hash, used));
+    }
 
-    let source_url: String = String::from("https://mock.blocknaut.xyz/generateTransferProof");
-    let response = client
-        .post(source_url)
-        .header("Content-Type", "application/json")
-        .header(
-            "Authorization",
-            format!("Bearer {}", &trx_authorization_answer.trim()),
-        )
-        .json(&request_body)
-        .send()
-        .await
-        .unwrap();
-
-    let mut proof_data = types::Proof::default();
-
-    match response.status() {
-        reqwest::StatusCode::OK => {
-            match response.json::<types::Proof>().await {
-                Ok(trx_info) => proof_data = trx_info,
-                Err(error) => println!(
-                    "Hm, the response didn't match the shape we expected. {}",
-                    error
-                ),
-            };
-        }
-        reqwest::StatusCode::UNAUTHORIZED => {
-            panic!("Need to grab a new token");
+    if args.read.json {
+        let entries = |checks: &[(H256, bool)]| {
+            checks
+                .iter()
+                .map(|(hash, used)| serde_json::json!({ "hash": format!("{:#x}", hash), "used": used }))
+                .collect::<Vec<_>>()
+        };
+        println!(
+            "{}",
+            serde_json::json!({
+                "reclaimProofs": entries(&reclaim_proofs),
+                "proofs": entries(&proofs),
+            })
+        );
+    } else {
+        let used = |used: &bool| if *used { "used" } else { "unused" };
+        for (hash, is_used) in reclaim_proofs.iter() {
+            println!("reclaim proof {:#x}: {}", hash, used(is_used));
         }
-        other => {
-            panic!("Uh oh! Something unexpected happened: {:?}", other);
+        for (hash, is_used) in proofs.iter() {
+            println!("proof {:#x}: {}", hash, used(is_used));
         }
-    };
+    }
+    Ok(())
+}
 
-    println!("Generating Proof ");
+async fn balance(args: BalanceArgs) -> Result<(), OfframpError> {
+    let jr_contract = read_contract(&args.read)?;
 
-    let mut stdin = SP1Stdin::new();
-    stdin.write(&serde_json::to_string(&proof_data).unwrap());
+    let balance = jr_contract
+        .balance_of(args.address)
+        .call()
+        .await
+        .map_err(OfframpError::from_contract_error)?;
+    let decimals = jr_contract
+        .decimals()
+        .call()
+        .await
+        .map_err(OfframpError::from_contract_error)?;
+    let balance_string = format_units(balance, u32::from(decimals))
+        .map_err(|e| OfframpError::Input(e.to_string()))?;
+
+    if args.read.json {
+        println!(
+            "{}",
+            serde_json::json!({
+                "address": format!("{:#x}", args.address),
+                "balance": balance.to_string(),
+                "decimals": decimals,
+            })
+        );
+    } else {
+        println!("{:#x}: {} stablecoins", args.address, balance_string);
+    }
+    Ok(())
+}
 
-    let cleaned_address_str = sender_address_answer.trim().trim_start_matches("0x");
-    let addr: Address = cleaned_address_str.parse().unwrap();
-    stdin.write(addr.as_fixed_bytes());
+async fn withdraw(args: WithdrawArgs) -> Result<(), OfframpError> {
+    let answers = Answers::load(args.prompts)?;
+    let signer = connect(&args.wallet, &answers).await?;
+    let jr_contract = JackRampContract::new(args.jackramp_contract_address, Arc::new(signer));
 
-    let client = ProverClient::new();
-    let (pk, vk) = client.setup(ZKTRANSFER_ELF);
+    let decimals = jr_contract
+        .decimals()
+        .call()
+        .await
+        .map_err(OfframpError::from_contract_error)?;
+    let amount: U256 = parse_units(&args.amount, u32::from(decimals))
+        .map_err(|e| OfframpError::Input(format!("invalid amount {}: {}", args.amount, e)))?
+        .into();
 
-    let proof: SP1ProofWithPublicValues = client
-        .prove(&pk, stdin)
-        .run()
-        .expect("failed to generate proof");
+    if !answers.confirm(&format!("Burn {} stablecoins to withdraw?", args.amount))? {
+        return Ok(());
+    }
 
-    println!("Successfully generated proof!");
+    let receipt = jr_contract
+        .withdraw(amount)
+        .send()
+        .await
+        .map_err(OfframpError::from_contract_error)?
+        .await
+        .map_err(|e| OfframpError::Contract(format!("Failed to submit tx {}", e)))?
+        .ok_or_else(|| OfframpError::Contract(String::from("Failed to withdraw: no receipt")))?;
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::json!({
+                "amount": amount.to_string(),
+                "transactionHash": format!("{:#x}", receipt.transaction_hash),
+            })
+        );
+    } else {
+        println!(
+            "Withdrew {} stablecoins. Transaction hash: {:x}",
+            args.amount, receipt.transaction_hash
+        );
+    }
+    Ok(())
+}
 
-    client.verify(&proof, &vk).expect("failed to verify proof");
-    println!("Successfully verified proof!");
+async fn fetch(
+    attestor: &AttestorClient,
+    answers: &Answers,
+    reclaim_proofs: &[PathBuf],
+) -> Result<(), OfframpError> {
+    println!("Welcome to the zkTransfer! Input the bank transaction, generate a zkProof, and claim your USD!");
+    println!("You will be asked to input Bank, Transaction ID, sender address, request timestamp, and Authorization token.");
 
-    // Serialize proof into bincode (format used by sp1)
-    let serialized_proof = bincode::serialize(&proof).expect("Failed to serialize proof");
+    let trx_authorization_answer = answers.authorization_token()?;
+    for (index, path) in reclaim_proofs.iter().enumerate() {
+        if reclaim_proofs.len() > 1 {
+            println!("Transfer {} of {}", index + 1, reclaim_proofs.len());
+        }
 
-    let verification_data = VerificationData {
-        proving_system: ProvingSystemId::SP1,
-        proof: serialized_proof,
-        proof_generator_addr: wallet.address(),
-        vm_program_code: Some(ZKTRANSFER_ELF.to_vec()),
-        verification_key: None,
-        pub_input: Some(proof.public_values.to_vec()),
-    };
+        let transfer = answers.transfer(index)?;
+        let sender_address = answers.sender_address(index)?;
 
-    let max_fee = estimate_fee(&rpc_url, PriceEstimate::Instant)
-        .await
-        .expect("failed to fetch gas price from the blockchain");
+        println!(
+            "Answers {}:{}:{}:***",
+            &transfer.channel_id, &transfer.transaction_id, &sender_address
+        );
 
-    let max_fee_string = ethers::utils::format_units(max_fee, 18).unwrap();
+        let request_body = bank_provider(&transfer.channel_id)?
+            .transfer_request(&transfer.transaction_id, &sender_address);
 
-    if !Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
-        .with_prompt(format!("Aligned will use at most {max_fee_string} eth to verify your proof. Do you want to continue?"))
-        .interact()
-        .expect("Failed to read user input")
-    {   return; }
+        let proof_data = attestor
+            .fetch_transfer_proof(&request_body, &trx_authorization_answer)
+            .await?;
+        save_reclaim_proof(path, &proof_data)?;
+        println!("Reclaim proof saved to {}", path.display());
+    }
 
-    let nonce = get_next_nonce(&rpc_url, wallet.address(), args.network)
-        .await
-        .expect("Failed to get next nonce");
+    Ok(())
+}
 
-    println!("Submitting your proof...");
+fn prove(
+    program: &ProgramArgs,
+    answers: &Answers,
+    reclaim_proofs: &[PathBuf],
+    settlement: Settlement,
+) -> Result<SP1ProofWithPublicValues, OfframpError> {
+    let mut inputs: Vec<types::OfframpInput> = Vec::new();
+
+    for (index, path) in reclaim_proofs.iter().enumerate() {
+        let transfer = answers.transfer(index)?;
+        let proof_data = load_reclaim_proof(path)?;
+        bank_provider(&transfer.channel_id)?
+            .find_transaction(&proof_data, &transfer.transaction_id)?;
+
+        inputs.push(types::OfframpInput {
+            proof: serde_json::to_string(&proof_data)
+                .map_err(|e| OfframpError::Schema(e.to_string()))?,
+            transaction_id: transfer.transaction_id,
+            channel_id: transfer.channel_id,
+            request_timestamp: answers.request_timestamp(index)?,
+        });
+    }
 
-    let aligned_verification_data = submit_and_wait_verification(
-        &args.batcher_url,
-        &rpc_url,
-        args.network,
-        &verification_data,
-        max_fee,
-        wallet.clone(),
-        nonce,
-    )
-    .await
-    .unwrap();
+    println!("Generating Proof ");
 
-    println!(
-        "Proof submitted and verified successfully on batch {}",
-        hex::encode(aligned_verification_data.batch_merkle_root)
+    let stdin = build_stdin(
+        &program.witnesses,
+        &program.oracles,
+        &load_rate_quote(&program.rate_quote)?,
+        program.max_transfer_age,
+        &inputs,
     );
 
-    println!("Claiming Stablecoins...");
+    let client = prover_client(program.mock);
+    let (pk, _) = client.setup(ZKTRANSFER_ELF);
 
-    claim_stablecoin_with_verified_proof(
-        &aligned_verification_data,
-        signer,
-        &args.jackramp_contract_address,
-        proof.public_values.as_slice(),
-    )
-    .await
-    .expect("Claiming of Stablecoins failed ...");
-}
-
-async fn claim_stablecoin_with_verified_proof(
-    aligned_verification_data: &AlignedVerificationData,
-    signer: SignerMiddleware<Provider<Http>, LocalWallet>,
-    jackramp_contract_addr: &Address,
-    pub_values: &[u8],
-) -> anyhow::Result<()> {
-    let jr_contract = JackRampContract::new(*jackramp_contract_addr, signer.into());
-    let index_in_batch = U256::from(aligned_verification_data.index_in_batch);
-    let ver_data_flattened_bytes: Vec<u8> = aligned_verification_data
-        .batch_inclusion_proof
-        .merkle_path
-        .as_slice()
-        .iter()
-        .flat_map(|array| array.to_vec())
-        .collect();
+    let mut prove = client.prove(&pk, stdin);
+    if settlement == Settlement::Plonk {
+        prove = prove.plonk();
+    }
+    let proof = prove
+        .run()
+        .map_err(|e| OfframpError::Proving(e.to_string()))?;
 
-    let merkle_path = Bytes::from(ver_data_flattened_bytes);
+    println!("Successfully generated proof!");
+    Ok(proof)
+}
 
-    let receipt = jr_contract
-        .fill_offramp(
-            aligned_verification_data
-                .verification_data_commitment
-                .proof_commitment,
-            aligned_verification_data
-                .verification_data_commitment
-                .pub_input_commitment,
-            aligned_verification_data
-                .verification_data_commitment
-                .proving_system_aux_data_commitment,
-            aligned_verification_data
-                .verification_data_commitment
-                .proof_generator_addr,
-            aligned_verification_data.batch_merkle_root,
-            merkle_path,
-            index_in_batch,
-            Bytes::from(pub_values.to_vec()),
-        )
-        .send()
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to send tx {}", e))?
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to submit tx {}", e))?;
-
-    match receipt {
-        Some(receipt) => {
-            println!(
-                "Stablecoin claimed successfully. Transaction hash: {:x}",
-                receipt.transaction_hash
-            );
-            Ok(())
-        }
-        None => {
-            anyhow::bail!("Failed to claim stablecoin: no receipt");
+/// Verifies `proof` with the SP1 verifier. Mock proofs have nothing to
+/// verify and are only accepted with `mock`.
+fn verify(proof: &SP1ProofWithPublicValues, mock: bool) -> Result<(), OfframpError> {
+    if is_mock_proof(proof) {
+        if !mock {
+            return Err(OfframpError::Verification(String::from(
+                "the proof is a mock proof, pass --mock to accept it unverified",
+            )));
         }
+        println!("Mock proof, nothing to verify");
+        return Ok(());
     }
+
+    let client = ProverClient::new();
+    let (_, vk) = client.setup(ZKTRANSFER_ELF);
+
+    client
+        .verify(proof, &vk)
+        .map_err(|e| OfframpError::Verification(e.to_string()))?;
+    println!("Successfully verified proof!");
+    Ok(())
 }
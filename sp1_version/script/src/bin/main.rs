@@ -1,14 +1,17 @@
-use script::types;
+use script::artifact::load_reclaim_proof;
+use script::attestor::{AttestorArgs, AttestorClient};
+use script::bank::bank_provider;
+use script::config::{Answers, PromptArgs};
+use script::{
+    build_stdin, load_rate_quote, prover_client, request_offramp_id, types,
+    OfframpError, DEFAULT_MAX_TRANSFER_AGE, DEFAULT_RECLAIM_WITNESS,
+};
 
-#[warn(unused_imports)]
 use alloy_sol_types::SolType;
 use clap::Parser;
-use reqwest;
-use sp1_sdk::{ProverClient, SP1Stdin};
-use std::io;
-use zktransfer_lib::{PublicValuesStruct, OfframpRequestParams};
+use std::path::PathBuf;
+use zktransfer_lib::PublicValuesStruct;
 use ethers::types::Address;
-use ethers::utils::keccak256;
 
 /// The ELF (executable and linkable format) file for the Succinct RISC-V zkVM.
 pub const ZKTRANSFER_ELF: &[u8] =
@@ -22,6 +25,38 @@ struct Args {
 
     #[clap(long)]
     prove: bool,
+
+    /// Reclaim witness addresses allowed to sign the claim.
+    #[clap(long, value_delimiter = ',', default_value = DEFAULT_RECLAIM_WITNESS)]
+    witnesses: Vec<Address>,
+
+    /// JSON file holding the signed exchange rate quote used to price the transfer.
+    #[clap(long)]
+    rate_quote: String,
+
+    /// Oracle addresses allowed to sign the exchange rate quote.
+    #[clap(long, value_delimiter = ',', required = true)]
+    oracles: Vec<Address>,
+
+    /// Maximum number of seconds between the bank transfer and its Reclaim claim.
+    #[clap(long, default_value_t = DEFAULT_MAX_TRANSFER_AGE)]
+    max_transfer_age: u32,
+
+    /// With `--prove`, only execute the program and return a mock proof
+    /// holding its real public values.
+    #[clap(long, env = "JACKRAMP_MOCK_PROVER")]
+    mock: bool,
+
+    /// Prove a saved Reclaim proof, or one read from stdin with `-`, instead
+    /// of fetching it from the attestor.
+    #[clap(long)]
+    reclaim_proof: Option<PathBuf>,
+
+    #[clap(flatten)]
+    attestor: AttestorArgs,
+
+    #[clap(flatten)]
+    prompts: PromptArgs,
 }
 
 #[tokio::main]
@@ -34,113 +69,97 @@ async fn main() {
         std::process::exit(1);
     }
 
+    if let Err(error) = run(args).await {
+        eprintln!("Error: {}", error);
+        std::process::exit(1);
+    }
+}
+
+async fn run(args: Args) -> Result<(), OfframpError> {
     println!("Welcome to the zkTransfer! Input the bank transaction, generate a zkProof, and claim your USD!");
-    println!("You will be asked to input Bank, Transaction ID, sender address, and Authorization token.");
-
-    println!("{}", "What is your Channel ID?");
-    let mut channel_id_answer = String::new();
-
-    io::stdin()
-        .read_line(&mut channel_id_answer)
-        .expect("Failed to read from stdin");
-
-    println!("{}", "Input your Transaction ID?");
-    let mut trx_id_answer = String::new();
-
-    io::stdin()
-        .read_line(&mut trx_id_answer)
-        .expect("Failed to read from stdin");
-    
-    println!("{}", "Input your Sender Address?");
-    let mut sender_address_answer = String::new();
-
-    io::stdin()
-        .read_line(&mut sender_address_answer)
-        .expect("Failed to read from stdin");
-
-    println!("{}", "Input your Authorization Token?");
-    let mut trx_authorization_answer = String::new();
-
-    io::stdin()
-        .read_line(&mut trx_authorization_answer)
-        .expect("Failed to read from stdin");
-
-    println!(
-        "Answers {}:{}:{}:{}",
-        &channel_id_answer.trim(),
-        &trx_id_answer.trim(),
-        &sender_address_answer.trim(),
-        &trx_authorization_answer.trim()
-    );
+    println!("You will be asked to input Bank, Transaction ID, sender address, request timestamp, and Authorization token.");
 
-    let client = reqwest::Client::new();
+    let answers = Answers::load(args.prompts.clone())?;
+    let transfer = answers.transfer(0)?;
+    let bank = bank_provider(&transfer.channel_id)?;
+    let request_timestamp = answers.request_timestamp(0)?;
 
-    let request_body = types::TransferRequest {
-        id: String::from(trx_id_answer.trim()),
-        bank: String::from(channel_id_answer.trim()),
-    };
+    let proof = match &args.reclaim_proof {
+        Some(path) => load_reclaim_proof(path)?,
+        None => {
+            let sender_address = answers.sender_address(0)?;
+            let trx_authorization_answer = answers.authorization_token()?;
 
-    let source_url: String = String::from("https://mock.blocknaut.xyz/generateTransferProof");
-    let response = client
-        .post(source_url)
-        .header("Content-Type", "application/json")
-        .header(
-            "Authorization",
-            format!("Bearer {}", &trx_authorization_answer.trim()),
-        )
-        .json(&request_body)
-        .send()
-        .await
-        .unwrap();
-
-    let mut proof = types::Proof::default();
-
-    match response.status() {
-        reqwest::StatusCode::OK => {
-            match response.json::<types::Proof>().await {
-                Ok(trx_info) => proof = trx_info,
-                Err(error) => println!(
-                    "Hm, the response didn't match the shape we expected. {}",
-                    error
-                ),
-            };
-        }
-        reqwest::StatusCode::UNAUTHORIZED => {
-            panic!("Need to grab a new token");
-        }
-        other => {
-            panic!("Uh oh! Something unexpected happened: {:?}", other);
+            println!(
+                "Answers {}:{}:{}:***",
+                &transfer.channel_id, &transfer.transaction_id, &sender_address
+            );
+
+            let request_body = bank.transfer_request(&transfer.transaction_id, &sender_address);
+
+            AttestorClient::new(&args.attestor)?
+                .fetch_transfer_proof(&request_body, &trx_authorization_answer)
+                .await?
         }
     };
 
-    println!("Generating Proof ");
+    bank.find_transaction(&proof, &transfer.transaction_id)?;
 
-    let mut stdin = SP1Stdin::new();
-    stdin.write(&serde_json::to_string(&proof).unwrap());
+    println!("Generating Proof ");
 
-    let cleaned_address_str = sender_address_answer.trim().trim_start_matches("0x");
-    let addr: Address = cleaned_address_str.parse().unwrap();
-    stdin.write(addr.as_fixed_bytes());
+    let inputs = vec![types::OfframpInput {
+        proof: serde_json::to_string(&proof).map_err(|e| OfframpError::Schema(e.to_string()))?,
+        transaction_id: transfer.transaction_id,
+        channel_id: transfer.channel_id,
+        request_timestamp,
+    }];
+    let stdin = build_stdin(
+        &args.witnesses,
+        &args.oracles,
+        &load_rate_quote(&args.rate_quote)?,
+        args.max_transfer_age,
+        &inputs,
+    );
 
-    let client = ProverClient::new();
+    let client = prover_client(args.mock);
 
     if args.execute {
-        let (output, report) = client.execute(ZKTRANSFER_ELF, stdin).run().unwrap();
+        let (output, report) = client
+            .execute(ZKTRANSFER_ELF, stdin)
+            .run()
+            .map_err(|e| OfframpError::Execution(e.to_string()))?;
         println!("Program executed successfully.");
 
         // Read the output.
-        let decoded = PublicValuesStruct::abi_decode(output.as_slice(), true).unwrap();
+        let decoded = PublicValuesStruct::abi_decode(output.as_slice(), true)
+            .map_err(|e| OfframpError::Schema(e.to_string()))?;
         println!("user: {}", decoded.offrampRequestParams.user);
         println!("amount: {}", decoded.offrampRequestParams.amount);
         println!("rw amount: {}", decoded.offrampRequestParams.amountRealWorld);
         println!("hash channel account: {:?}", decoded.offrampRequestParams.hashedChannelAccount);
         println!("hash channel id: {:?}", decoded.offrampRequestParams.hashedChannelId);
-
-        let bytes = OfframpRequestParams::abi_encode(&decoded.offrampRequestParams);
-        let hash = keccak256(bytes);
-
-        // Print the resulting hash in hexadecimal format
-        println!("requestOfframpId: 0x{:x}", ethers::types::U256::from(hash));
+        println!("hash currency: {:?}", decoded.hashedCurrency);
+        println!(
+            "transfer timestamp: {} (requested at {}, max age {}s)",
+            decoded.transferWindow.transferTimestamp,
+            decoded.transferWindow.requestTimestamp,
+            decoded.transferWindow.maxTransferAge
+        );
+        println!("witnesses: {:?}", decoded.witnesses);
+        println!("transaction index: {}", decoded.transactionIndex);
+        println!("hash transaction id: {:?}", decoded.hashedTransactionId);
+        println!(
+            "exchange rate: {} ({} decimals) at {} signed by {}",
+            decoded.exchangeRate.rate,
+            decoded.exchangeRate.decimals,
+            decoded.exchangeRate.timestampS,
+            decoded.exchangeRate.oracle
+        );
+
+        println!(
+            "requestOfframpId: 0x{:x}",
+            request_offramp_id(&decoded.offrampRequestParams)
+        );
 
         // Record the number of cycles executed.
         println!("Number of cycles: {}", report.total_instruction_count());
@@ -150,11 +169,19 @@ async fn main() {
         let proof = client
             .prove(&pk, stdin)
             .run()
-            .expect("failed to generate proof");
+            .map_err(|e| OfframpError::Proving(e.to_string()))?;
 
         println!("Successfully generated proof!");
 
-        client.verify(&proof, &vk).expect("failed to verify proof");
-        println!("Successfully verified proof!");
+        if args.mock {
+            println!("Mock proof, nothing to verify");
+        } else {
+            client
+                .verify(&proof, &vk)
+                .map_err(|e| OfframpError::Verification(e.to_string()))?;
+            println!("Successfully verified proof!");
+        }
     }
+
+    Ok(())
 }
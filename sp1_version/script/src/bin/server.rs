@@ -1,3 +1,340 @@
-fn main() {
-    println!("huhui");
+use script::artifact::validate_reclaim_proof;
+use script::bank::bank_provider;
+use script::jobs::{Job, JobMode, JobProver, JobRequest, JobStore, Stage};
+use script::{types, OfframpError, DEFAULT_MAX_TRANSFER_AGE, DEFAULT_RECLAIM_WITNESS};
+
+use axum::extract::{Path, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use clap::Parser;
+use ethers::types::Address;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{mpsc, Mutex};
+
+/// The ELF (executable and linkable format) file for the Succinct RISC-V zkVM.
+pub const ZKTRANSFER_ELF: &[u8] =
+    include_bytes!("../../../program/elf/riscv32im-succinct-zkvm-elf");
+
+#[derive(Parser, Debug)]
+#[command(version, about = "Execute and prove offramps over HTTP", long_about = None)]
+struct Args {
+    /// Address the HTTP API listens on.
+    #[arg(long, env = "JACKRAMP_SERVER_LISTEN", default_value = "127.0.0.1:8080")]
+    listen: SocketAddr,
+
+    /// Bearer token every request must carry in its `Authorization` header.
+    #[arg(long, env = "JACKRAMP_SERVER_TOKEN")]
+    token: Option<String>,
+
+    /// Number of jobs waiting for a worker before `POST /jobs` answers 503.
+    #[arg(long, env = "JACKRAMP_SERVER_QUEUE_SIZE", default_value_t = 64)]
+    queue_size: usize,
+
+    /// Number of jobs executed or proved at once.
+    #[arg(long, env = "JACKRAMP_SERVER_WORKERS", default_value_t = 1)]
+    workers: usize,
+
+    /// Directory holding the job database and the bincode SP1 proofs.
+    #[arg(long, env = "JACKRAMP_SERVER_DATA_DIR", default_value = "jobs")]
+    data_dir: PathBuf,
+
+    /// Reclaim witness addresses allowed to sign the claim.
+    #[arg(long, value_delimiter = ',', default_value = DEFAULT_RECLAIM_WITNESS)]
+    witnesses: Vec<Address>,
+
+    /// Oracle addresses allowed to sign the exchange rate quote.
+    #[arg(long, value_delimiter = ',', required = true)]
+    oracles: Vec<Address>,
+
+    /// Maximum number of seconds between the bank transfer and its Reclaim claim.
+    #[arg(long, default_value_t = DEFAULT_MAX_TRANSFER_AGE)]
+    max_transfer_age: u32,
+
+    /// Only execute the program and store mock proofs holding its real
+    /// public values.
+    #[arg(long, env = "JACKRAMP_MOCK_PROVER")]
+    mock: bool,
+}
+
+/// Everything a worker needs to run a job.
+struct Worker {
+    store: Arc<JobStore>,
+    prover: JobProver,
+}
+
+#[derive(Clone)]
+struct AppState {
+    store: Arc<JobStore>,
+    queue: mpsc::Sender<String>,
+    token: Option<Arc<str>>,
+}
+
+/// A JSON `{"error": ...}` answer.
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+impl From<OfframpError> for ApiError {
+    fn from(error: OfframpError) -> Self {
+        let status = match error {
+            OfframpError::Io(_) | OfframpError::Store(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        ApiError(status, error.to_string())
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    sp1_sdk::utils::setup_logger();
+
+    if let Err(error) = run(Args::parse()).await {
+        eprintln!("Error: {}", error);
+        std::process::exit(1);
+    }
+}
+
+async fn run(args: Args) -> Result<(), OfframpError> {
+    if args.workers == 0 {
+        return Err(OfframpError::Input(String::from(
+            "--workers must be at least 1",
+        )));
+    }
+    if args.queue_size == 0 {
+        return Err(OfframpError::Input(String::from(
+            "--queue-size must be at least 1",
+        )));
+    }
+    if args.token.is_none() && !args.listen.ip().is_loopback() {
+        log::warn!(
+            "listening on {} without --token, anyone who can reach it can queue jobs",
+            args.listen
+        );
+    }
+
+    let store = Arc::new(JobStore::open(&args.data_dir)?);
+    let worker = Arc::new(Worker {
+        store: Arc::clone(&store),
+        prover: JobProver::new(
+            ZKTRANSFER_ELF,
+            args.witnesses,
+            args.oracles,
+            args.max_transfer_age,
+            args.mock,
+        ),
+    });
+
+    let (queue, jobs) = mpsc::channel(args.queue_size);
+    let jobs = Arc::new(Mutex::new(jobs));
+    for _ in 0..args.workers {
+        tokio::spawn(work(Arc::clone(&worker), Arc::clone(&jobs)));
+    }
+
+    // Failed jobs are retried from their last completed stage along with the
+    // unfinished ones. They may outnumber the queue, so they wait for room in
+    // the background instead of holding up the API.
+    let retried = store.retry_failed()?;
+    if retried > 0 {
+        println!("Retrying {} failed job(s)", retried);
+    }
+    let unfinished = store.unfinished()?;
+    let resumed = queue.clone();
+    tokio::spawn(async move {
+        for job in unfinished {
+            println!("Resuming job {} from stage {:?}", job.id, job.stage);
+            if resumed.send(job.id).await.is_err() {
+                return;
+            }
+        }
+    });
+
+    let state = AppState {
+        store,
+        queue,
+        token: args.token.map(Arc::from),
+    };
+    let app = Router::new()
+        .route("/jobs", post(create_job))
+        .route("/jobs/:id", get(job_status))
+        .route("/jobs/:id/proof", get(job_proof))
+        .layer(middleware::from_fn_with_state(state.clone(), authorize))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(args.listen).await?;
+    println!(
+        "Proving server listening on {} with {} worker(s)",
+        args.listen, args.workers
+    );
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
+
+/// Rejects requests without the `--token` bearer token, when one is set.
+async fn authorize(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    if let Some(token) = &state.token {
+        let authorized = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|bearer| constant_time_eq(bearer.as_bytes(), token.as_bytes()));
+        if !authorized {
+            return Err(ApiError(
+                StatusCode::UNAUTHORIZED,
+                String::from("missing or wrong bearer token"),
+            ));
+        }
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Compares two byte strings in time independent of where they differ.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Takes queued job ids one at a time and runs them off the async runtime.
+async fn work(worker: Arc<Worker>, jobs: Arc<Mutex<mpsc::Receiver<String>>>) {
+    loop {
+        let Some(id) = jobs.lock().await.recv().await else {
+            return;
+        };
+
+        let runner = Arc::clone(&worker);
+        let job_id = id.clone();
+        let result = tokio::task::spawn_blocking(move || runner.run_job(&job_id))
+            .await
+            .unwrap_or_else(|e| Err(OfframpError::Proving(e.to_string())));
+
+        match result {
+            Ok(()) => log::info!("job {} done", id),
+            Err(error) => {
+                log::error!("job {} failed: {}", id, error);
+                if let Err(error) = worker.store.record_failure(&id, &error.to_string()) {
+                    log::error!("failed to record the failure of job {}: {}", id, error);
+                }
+            }
+        }
+    }
+}
+
+impl Worker {
+    fn run_job(&self, id: &str) -> Result<(), OfframpError> {
+        let job = self
+            .store
+            .get(id)?
+            .ok_or_else(|| OfframpError::Input(format!("unknown job {}", id)))?;
+
+        self.prover.run(&self.store, &job)
+    }
+}
+
+/// `POST /jobs`: checks the Reclaim proof covers the transfer and pays the
+/// recipient, then queues it.
+async fn create_job(
+    State(state): State<AppState>,
+    Json(request): Json<JobRequest>,
+) -> Result<(StatusCode, Json<Job>), ApiError> {
+    if request.mode == JobMode::Claim {
+        return Err(ApiError(
+            StatusCode::BAD_REQUEST,
+            String::from("the proving server only executes and proves, use `evm` to claim"),
+        ));
+    }
+    validate_reclaim_proof(&request.proof)?;
+    bank_provider(&request.channel_id)?
+        .find_transaction(&request.proof, &request.transaction_id)?;
+
+    let context: types::Context = serde_json::from_str(&request.proof.claim_info.context)
+        .map_err(|e| OfframpError::Schema(format!("claimInfo.context: {}", e)))?;
+    let recipient: Address = request
+        .recipient
+        .parse()
+        .map_err(|_| OfframpError::Input(format!("invalid recipient {}", request.recipient)))?;
+    if context.context_address.parse::<Address>().ok() != Some(recipient) {
+        return Err(ApiError(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "the Reclaim proof pays {}, not {}",
+                context.context_address, request.recipient
+            ),
+        ));
+    }
+
+    // Take a slot in the queue before storing the job, so a full queue
+    // rejects the job instead of leaving it stored but never run.
+    let slot = state.queue.try_reserve().map_err(|error| {
+        let reason = match error {
+            TrySendError::Full(()) => "the job queue is full, retry later",
+            TrySendError::Closed(()) => "no workers",
+        };
+        ApiError(StatusCode::SERVICE_UNAVAILABLE, String::from(reason))
+    })?;
+    let job = state.store.insert(request)?;
+    slot.send(job.id.clone());
+
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+/// `GET /jobs/:id`: stage, cycle count and public values of a job.
+async fn job_status(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Job>, ApiError> {
+    state
+        .store
+        .get(&id)?
+        .map(Json)
+        .ok_or_else(|| ApiError(StatusCode::NOT_FOUND, format!("unknown job {}", id)))
+}
+
+/// `GET /jobs/:id/proof`: the bincode SP1 proof, once a prove job is done.
+async fn job_proof(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let job = state
+        .store
+        .get(&id)?
+        .ok_or_else(|| ApiError(StatusCode::NOT_FOUND, format!("unknown job {}", id)))?;
+    if job.stage != Stage::Proved {
+        return Err(ApiError(
+            StatusCode::CONFLICT,
+            format!("job {} has no proof", id),
+        ));
+    }
+
+    let proof = tokio::fs::read(state.store.proof_path(&id))
+        .await
+        .map_err(|e| ApiError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                String::from("application/octet-stream"),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}.bin\"", id),
+            ),
+        ],
+        proof,
+    ))
 }
@@ -0,0 +1,36 @@
+use script::solver::{Solver, SolverArgs};
+use script::OfframpError;
+
+use clap::Parser;
+use std::time::Duration;
+
+/// The ELF (executable and linkable format) file for the Succinct RISC-V zkVM.
+pub const ZKTRANSFER_ELF: &[u8] =
+    include_bytes!("../../../program/elf/riscv32im-succinct-zkvm-elf");
+
+#[tokio::main]
+async fn main() {
+    sp1_sdk::utils::setup_logger();
+
+    if let Err(error) = run(SolverArgs::parse()).await {
+        eprintln!("Error: {}", error);
+        std::process::exit(1);
+    }
+}
+
+async fn run(args: SolverArgs) -> Result<(), OfframpError> {
+    let mut solver = Solver::new(args, ZKTRANSFER_ELF).await?;
+    println!(
+        "Solver {:#x} watching {:#x} from block {}",
+        solver.address(),
+        solver.args().jackramp_contract_address,
+        solver.next_block()
+    );
+
+    loop {
+        if let Err(error) = solver.poll().await {
+            log::error!("poll failed: {}", error);
+        }
+        tokio::time::sleep(Duration::from_secs(solver.args().poll_interval)).await;
+    }
+}
@@ -0,0 +1,297 @@
+use dialoguer::Confirm;
+use ethers::types::{Address, U256};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use crate::error::OfframpError;
+use crate::{
+    parse_request_timestamp, read_answer, DEFAULT_MAX_TRANSFER_AGE, DEFAULT_RECLAIM_WITNESS,
+};
+
+/// Flags for every value the binaries would otherwise prompt for. Each one
+/// falls back to its environment variable, then to the config file, and only
+/// then to an interactive prompt.
+#[derive(clap::Args, Debug, Default, Clone)]
+pub struct PromptArgs {
+    /// TOML or JSON file providing any of the prompted values.
+    #[arg(long, env = "JACKRAMP_CONFIG")]
+    pub config: Option<PathBuf>,
+    #[arg(long, env = "JACKRAMP_CHANNEL_ID")]
+    pub channel_id: Option<String>,
+    #[arg(long, env = "JACKRAMP_TRANSACTION_ID")]
+    pub transaction_id: Option<String>,
+    #[arg(long, env = "JACKRAMP_SENDER_ADDRESS")]
+    pub sender_address: Option<String>,
+    #[arg(long, env = "JACKRAMP_REQUEST_TIMESTAMP")]
+    pub request_timestamp: Option<u32>,
+    #[arg(long, env = "JACKRAMP_AUTHORIZATION_TOKEN", hide_env_values = true)]
+    pub authorization_token: Option<String>,
+    /// Never prompt: fail on missing values and answer confirmations from the
+    /// configured policy.
+    #[arg(long, env = "JACKRAMP_YES")]
+    pub yes: bool,
+}
+
+/// Program inputs that don't come from the bank transfer itself.
+#[derive(clap::Args, Debug, Clone)]
+pub struct ProgramArgs {
+    /// Reclaim witness addresses allowed to sign the claim.
+    #[arg(long, value_delimiter = ',', default_value = DEFAULT_RECLAIM_WITNESS)]
+    pub witnesses: Vec<Address>,
+    /// JSON file holding the signed exchange rate quote used to price the transfers.
+    #[arg(long)]
+    pub rate_quote: String,
+    /// Oracle addresses allowed to sign the exchange rate quote.
+    #[arg(long, value_delimiter = ',', required = true)]
+    pub oracles: Vec<Address>,
+    /// Maximum number of seconds between a bank transfer and its Reclaim claim.
+    #[arg(long, default_value_t = DEFAULT_MAX_TRANSFER_AGE)]
+    pub max_transfer_age: u32,
+    /// Only execute the program and write a mock proof holding its real
+    /// public values. Their Aligned verification is stubbed.
+    #[arg(long, env = "JACKRAMP_MOCK_PROVER")]
+    pub mock: bool,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TransferConfig {
+    pub channel_id: Option<String>,
+    pub transaction_id: Option<String>,
+    pub sender_address: Option<String>,
+    pub request_timestamp: Option<u32>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct FileConfig {
+    pub authorization_token: Option<String>,
+    pub keystore_password: Option<String>,
+    pub yes: Option<bool>,
+    /// Deposit to Aligned without asking when running with `--yes`.
+    pub deposit: Option<bool>,
+    /// Highest Aligned fee, in ETH, accepted without asking.
+    pub max_fee: Option<String>,
+    #[serde(default)]
+    pub transfers: Vec<TransferConfig>,
+}
+
+impl FileConfig {
+    /// Reads a config file, as JSON when it has a `.json` extension and as
+    /// TOML otherwise.
+    pub fn load(path: &Path) -> Result<Self, OfframpError> {
+        let raw_config = std::fs::read_to_string(path)?;
+        let invalid = |error: String| {
+            OfframpError::Input(format!("invalid config file {}: {}", path.display(), error))
+        };
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => serde_json::from_str(&raw_config).map_err(|e| invalid(e.to_string())),
+            _ => toml::from_str(&raw_config).map_err(|e| invalid(e.to_string())),
+        }
+    }
+}
+
+/// The answers identifying one bank transfer.
+#[derive(Debug, Clone)]
+pub struct TransferAnswers {
+    pub channel_id: String,
+    pub transaction_id: String,
+}
+
+/// Resolves prompted values from flags, environment and config file.
+#[derive(Debug, Default)]
+pub struct Answers {
+    args: PromptArgs,
+    file: FileConfig,
+}
+
+impl Answers {
+    pub fn load(args: PromptArgs) -> Result<Self, OfframpError> {
+        let file = match &args.config {
+            Some(path) => FileConfig::load(path)?,
+            None => FileConfig::default(),
+        };
+
+        Ok(Answers { args, file })
+    }
+
+    /// Whether prompts are disabled.
+    pub fn yes(&self) -> bool {
+        self.args.yes || self.file.yes.unwrap_or(false)
+    }
+
+    /// Number of transfers described by the config file, at least one.
+    pub fn transfer_count(&self) -> usize {
+        self.file.transfers.len().max(1)
+    }
+
+    fn answer(
+        &self,
+        name: &str,
+        flag: Option<&String>,
+        file: Option<&String>,
+        question: &str,
+    ) -> Result<String, OfframpError> {
+        match flag.or(file) {
+            Some(value) => Ok(value.clone()),
+            None if self.yes() => Err(OfframpError::Input(format!(
+                "missing `{}` in non-interactive mode",
+                name
+            ))),
+            None => read_answer(question),
+        }
+    }
+
+    pub fn authorization_token(&self) -> Result<String, OfframpError> {
+        self.answer(
+            "authorization_token",
+            self.args.authorization_token.as_ref(),
+            self.file.authorization_token.as_ref(),
+            "Input your Authorization Token?",
+        )
+    }
+
+    /// Flags and config file entry for the `index`th transfer. Flags only
+    /// describe the first one.
+    fn transfer_sources(&self, index: usize) -> (PromptArgs, TransferConfig) {
+        let file = self.file.transfers.get(index).cloned().unwrap_or_default();
+        let flags = if index == 0 {
+            self.args.clone()
+        } else {
+            PromptArgs::default()
+        };
+
+        (flags, file)
+    }
+
+    /// Bank channel of the `index`th transfer, e.g. `bca`.
+    pub fn channel_id(&self, index: usize) -> Result<String, OfframpError> {
+        let (flags, file) = self.transfer_sources(index);
+
+        self.answer(
+            "channel_id",
+            flags.channel_id.as_ref(),
+            file.channel_id.as_ref(),
+            "What is your Channel ID?",
+        )
+    }
+
+    /// Answers identifying the `index`th bank transfer.
+    pub fn transfer(&self, index: usize) -> Result<TransferAnswers, OfframpError> {
+        let (flags, file) = self.transfer_sources(index);
+
+        let channel_id = self.channel_id(index)?;
+        let transaction_id = self.answer(
+            "transaction_id",
+            flags.transaction_id.as_ref(),
+            file.transaction_id.as_ref(),
+            "Input your Transaction ID?",
+        )?;
+
+        Ok(TransferAnswers {
+            channel_id,
+            transaction_id,
+        })
+    }
+
+    /// When the offramp paid by the `index`th transfer was requested. Only
+    /// needed when proving.
+    pub fn request_timestamp(&self, index: usize) -> Result<u32, OfframpError> {
+        let (flags, file) = self.transfer_sources(index);
+
+        match flags.request_timestamp.or(file.request_timestamp) {
+            Some(request_timestamp) => Ok(request_timestamp),
+            None => parse_request_timestamp(&self.answer(
+                "request_timestamp",
+                None,
+                None,
+                "Input your Offramp Request Timestamp?",
+            )?),
+        }
+    }
+
+    /// Address the attestor binds the `index`th transfer's claim to. Only
+    /// needed when fetching a Reclaim proof.
+    pub fn sender_address(&self, index: usize) -> Result<String, OfframpError> {
+        let (flags, file) = self.transfer_sources(index);
+
+        self.answer(
+            "sender_address",
+            flags.sender_address.as_ref(),
+            file.sender_address.as_ref(),
+            "Input your Sender Address?",
+        )
+    }
+
+    pub fn keystore_password(&self, flag: Option<&String>) -> Result<String, OfframpError> {
+        match flag.or(self.file.keystore_password.as_ref()) {
+            Some(password) => Ok(password.clone()),
+            None if self.yes() => Err(OfframpError::Input(String::from(
+                "missing `keystore_password` in non-interactive mode",
+            ))),
+            None => Ok(rpassword::prompt_password("Enter keystore password: ")?),
+        }
+    }
+
+    /// Asks `question`, which is taken as accepted under `--yes`.
+    pub fn confirm(&self, question: &str) -> Result<bool, OfframpError> {
+        if self.yes() {
+            return Ok(true);
+        }
+
+        confirm(question)
+    }
+
+    /// Asks `question`, or answers it with the `deposit` policy under `--yes`.
+    pub fn confirm_deposit(&self, flag: bool, question: &str) -> Result<bool, OfframpError> {
+        if self.yes() {
+            return Ok(flag || self.file.deposit.unwrap_or(false));
+        }
+
+        confirm(question)
+    }
+
+    /// Highest fee accepted without asking, from the flag or config file.
+    pub fn max_fee(&self, flag: Option<&String>) -> Result<Option<U256>, OfframpError> {
+        flag.or(self.file.max_fee.as_ref())
+            .map(|max_fee| {
+                ethers::utils::parse_ether(max_fee)
+                    .map_err(|e| OfframpError::Input(format!("invalid max fee {}: {}", max_fee, e)))
+            })
+            .transpose()
+    }
+
+    /// Rejects fees above the ceiling. Under `--yes` a ceiling is mandatory
+    /// and any fee below it is accepted, otherwise `question` is asked.
+    pub fn confirm_fee(
+        &self,
+        fee: U256,
+        ceiling: Option<U256>,
+        question: &str,
+    ) -> Result<bool, OfframpError> {
+        if let Some(ceiling) = ceiling {
+            if fee > ceiling {
+                return Err(OfframpError::Input(format!(
+                    "estimated fee {} wei is above the {} wei ceiling",
+                    fee, ceiling
+                )));
+            }
+        }
+
+        match ceiling {
+            Some(_) if self.yes() => Ok(true),
+            None if self.yes() => Err(OfframpError::Input(String::from(
+                "a max fee is required in non-interactive mode",
+            ))),
+            _ => confirm(question),
+        }
+    }
+}
+
+fn confirm(question: &str) -> Result<bool, OfframpError> {
+    Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
+        .with_prompt(question)
+        .interact()
+        .map_err(|e| OfframpError::Input(e.to_string()))
+}
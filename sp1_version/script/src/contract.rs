@@ -0,0 +1,55 @@
+use alloy_primitives::{Address, B256, U256};
+use ethers::contract::abigen;
+
+abigen!(JackRampContract, "JackRampContract.json");
+
+// Batch settlement, NOT part of the deployed `JackRampContract` ABI above. Only
+// a contract built with `fillOfframpBatch`, taking the same arguments as
+// `fillOfframp` with `BatchPublicValuesStruct` public values, accepts it, so
+// claims only route here with `--batch-settlement`.
+abigen!(
+    JackRampBatchContract,
+    r#"[
+        function fillOfframpBatch(bytes32 proofCommitment, bytes32 pubInputCommitment, bytes32 provingSystemAuxDataCommitment, bytes20 proofGeneratorAddr, bytes32 batchMerkleRoot, bytes merkleProof, uint256 verificationDataBatchIndex, bytes pubInputBytes) external
+    ]"#
+);
+
+// The contract side of `--settlement plonk`: a `JackRampContract` variant that
+// checks `proofBytes` with `verifyProof(programVKey(), publicValues,
+// proofBytes)` on the SP1 verifier gateway at `verifier()` instead of reading
+// Aligned batches, and reverts with the same custom errors. Like
+// `fillOfframpBatch`, it is not part of the deployed ABI above.
+abigen!(
+    JackRampGatewayContract,
+    r#"[
+        function fillOfframpWithSp1Proof(bytes publicValues, bytes proofBytes) external
+        function fillOfframpBatchWithSp1Proof(bytes publicValues, bytes proofBytes) external
+        function verifier() external view returns (address)
+        function programVKey() external view returns (bytes32)
+    ]"#
+);
+
+// `SP1VerifierGateway` and the PLONK `SP1Verifier` it routes proofs to, with
+// the ABIs of succinctlabs/sp1-contracts. The gateway picks the verifier by the
+// first 4 bytes of `proofBytes`, the verifier's `VERIFIER_HASH`.
+abigen!(SP1VerifierGateway, "SP1VerifierGateway.json");
+
+/// Bindings of the PLONK `SP1Verifier`, kept apart as both contracts have a
+/// `verifyProof`.
+pub mod plonk_verifier {
+    ethers::contract::abigen!(SP1VerifierPlonk, "SP1VerifierPlonk.json");
+}
+
+impl From<&OfframpRequestParams> for zktransfer_lib::OfframpRequestParams {
+    /// The params as the zkVM program commits them, to compute the
+    /// `requestOfframpId` of a request.
+    fn from(params: &OfframpRequestParams) -> Self {
+        zktransfer_lib::OfframpRequestParams {
+            user: Address::from(params.user.to_fixed_bytes()),
+            amount: U256::from_limbs(params.amount.0),
+            amountRealWorld: U256::from_limbs(params.amount_real_world.0),
+            hashedChannelAccount: B256::from(params.channel_account),
+            hashedChannelId: B256::from(params.channel_id),
+        }
+    }
+}
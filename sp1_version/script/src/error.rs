@@ -0,0 +1,74 @@
+use ethers::contract::ContractError;
+use ethers::providers::Middleware;
+use thiserror::Error;
+use zktransfer_lib::verify::reclaim::ClaimError;
+
+use crate::contract::JackRampContractErrors;
+use crate::revert::{decode_revert, describe_revert};
+
+/// Everything that can go wrong between fetching a bank transfer proof and
+/// claiming the stablecoins for it.
+#[derive(Debug, Error)]
+pub enum OfframpError {
+    #[error("invalid input: {0}")]
+    Input(String),
+
+    #[error("failed to read input: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("failed to reach the attestor: {0}")]
+    Fetch(#[from] reqwest::Error),
+
+    #[error("the attestor rejected the authorization token, grab a new one")]
+    Unauthorized,
+
+    #[error("the attestor answered with unexpected status {0}")]
+    UnexpectedStatus(reqwest::StatusCode),
+
+    #[error("the response didn't match the shape we expected: {0}")]
+    Schema(String),
+
+    #[error("invalid Reclaim proof: {0}")]
+    Claim(#[from] ClaimError),
+
+    #[error("failed to execute the program: {0}")]
+    Execution(String),
+
+    #[error("failed to generate proof: {0}")]
+    Proving(String),
+
+    #[error("failed to verify proof: {0}")]
+    Verification(String),
+
+    #[error("wallet error: {0}")]
+    Wallet(String),
+
+    #[error("rpc error: {0}")]
+    Rpc(String),
+
+    #[error("aligned error: {0}")]
+    Aligned(String),
+
+    #[error("contract reverted with {}", describe_revert(.0))]
+    Revert(JackRampContractErrors),
+
+    #[error("fillOfframp would revert with {}, not submitting to Aligned", describe_revert(.0))]
+    WouldRevert(JackRampContractErrors),
+
+    #[error("contract call failed: {0}")]
+    Contract(String),
+
+    #[error("job store error: {0}")]
+    Store(#[from] rusqlite::Error),
+}
+
+impl OfframpError {
+    /// Decodes the revert payload of a failed contract call into the custom
+    /// errors of `JackRampContract`, falling back to the provider message.
+    pub fn from_contract_error<M: Middleware>(error: ContractError<M>) -> Self {
+        match error.as_revert().and_then(|data| decode_revert(data)) {
+            Some(revert) => OfframpError::Revert(revert),
+            None => OfframpError::Contract(error.to_string()),
+        }
+    }
+}
@@ -0,0 +1,344 @@
+use aligned_sdk::core::types::{
+    AlignedVerificationData, Network, PriceEstimate, ProvingSystemId, VerificationData,
+    VerificationDataCommitment,
+};
+use aligned_sdk::sdk::{deposit_to_aligned, estimate_fee};
+use aligned_sdk::sdk::{get_next_nonce, submit_and_wait_verification};
+use ethers::prelude::*;
+use ethers::providers::{Http, Provider};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{Address, Bytes, U256};
+use sp1_sdk::SP1ProofWithPublicValues;
+use std::sync::Arc;
+use zktransfer_lib::{decode_public_values, is_batch_public_values};
+
+use crate::config::Answers;
+use crate::contract::{
+    JackRampBatchContract, JackRampContract, JackRampContractErrors, OfframpRequestAlreadyProved,
+    OfframpRequestDoesNotExist, ReclaimProofAlreadyUsed,
+};
+use crate::error::OfframpError;
+use crate::status::OfframpRequestState;
+use crate::{is_mock_proof, request_offramp_id};
+
+pub type EvmSigner = SignerMiddleware<Provider<Http>, LocalWallet>;
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct WalletArgs {
+    #[arg(short, long)]
+    pub keystore_path: String,
+    #[arg(
+        short,
+        long,
+        default_value = "https://ethereum-holesky-rpc.publicnode.com"
+    )]
+    pub rpc_url: String,
+    #[arg(short, long, default_value = "holesky")]
+    pub network: Network,
+    #[arg(long, env = "JACKRAMP_KEYSTORE_PASSWORD", hide_env_values = true)]
+    pub keystore_password: Option<String>,
+}
+
+pub async fn connect(
+    wallet_args: &WalletArgs,
+    answers: &Answers,
+) -> Result<EvmSigner, OfframpError> {
+    let keystore_password = answers.keystore_password(wallet_args.keystore_password.as_ref())?;
+
+    let provider = Provider::<Http>::try_from(wallet_args.rpc_url.as_str())
+        .map_err(|e| OfframpError::Rpc(e.to_string()))?;
+
+    let chain_id = provider
+        .get_chainid()
+        .await
+        .map_err(|e| OfframpError::Rpc(e.to_string()))?;
+
+    let wallet = LocalWallet::decrypt_keystore(&wallet_args.keystore_path, &keystore_password)
+        .map_err(|e| OfframpError::Wallet(e.to_string()))?
+        .with_chain_id(chain_id.as_u64());
+
+    Ok(SignerMiddleware::new(provider, wallet))
+}
+
+pub async fn deposit(signer: EvmSigner, network: Network) -> Result<(), OfframpError> {
+    deposit_to_aligned(U256::from(4000000000000000u128), signer, network)
+        .await
+        .map_err(|e| OfframpError::Aligned(format!("{:?}", e)))?;
+
+    println!("Deposited 0.004eth to Aligned");
+    Ok(())
+}
+
+/// Submits the proof of `elf` to Aligned, returning `None` when the fee is
+/// declined. Mock proofs are only accepted with `mock`, and never reach
+/// Aligned: their verification is stubbed.
+#[allow(clippy::too_many_arguments)]
+pub async fn submit(
+    signer: &EvmSigner,
+    answers: &Answers,
+    wallet_args: &WalletArgs,
+    batcher_url: &str,
+    max_fee_ceiling: Option<U256>,
+    elf: &[u8],
+    proof: &SP1ProofWithPublicValues,
+    mock: bool,
+) -> Result<Option<AlignedVerificationData>, OfframpError> {
+    match (mock, is_mock_proof(proof)) {
+        (false, true) => {
+            return Err(OfframpError::Input(String::from(
+                "the proof is a mock proof, which Aligned rejects: pass --mock to stub its verification",
+            )))
+        }
+        (true, false) => {
+            return Err(OfframpError::Input(String::from(
+                "--mock only stubs the verification of mock proofs, submit this proof without it",
+            )))
+        }
+        _ => {}
+    }
+
+    let wallet = signer.signer().clone();
+    let rpc_url = &wallet_args.rpc_url;
+
+    // Serialize proof into bincode (format used by sp1)
+    let serialized_proof =
+        bincode::serialize(proof).map_err(|e| OfframpError::Proving(e.to_string()))?;
+
+    let verification_data = VerificationData {
+        proving_system: ProvingSystemId::SP1,
+        proof: serialized_proof,
+        proof_generator_addr: wallet.address(),
+        vm_program_code: Some(elf.to_vec()),
+        verification_key: None,
+        pub_input: Some(proof.public_values.to_vec()),
+    };
+
+    if mock {
+        println!("Mock proving, stubbing the Aligned verification");
+        return mock_aligned_verification_data(verification_data).map(Some);
+    }
+
+    let max_fee = estimate_fee(rpc_url, PriceEstimate::Instant)
+        .await
+        .map_err(|e| OfframpError::Aligned(format!("{:?}", e)))?;
+
+    let max_fee_string = ethers::utils::format_units(max_fee, 18)
+        .map_err(|e| OfframpError::Aligned(e.to_string()))?;
+
+    if !answers.confirm_fee(
+        max_fee,
+        max_fee_ceiling,
+        &format!("Aligned will use at most {max_fee_string} eth to verify your proof. Do you want to continue?"),
+    )? {
+        return Ok(None);
+    }
+
+    let nonce = get_next_nonce(rpc_url, wallet.address(), wallet_args.network)
+        .await
+        .map_err(|e| OfframpError::Aligned(format!("{:?}", e)))?;
+
+    println!("Submitting your proof...");
+
+    let aligned_verification_data = submit_and_wait_verification(
+        batcher_url,
+        rpc_url,
+        wallet_args.network,
+        &verification_data,
+        max_fee,
+        wallet,
+        nonce,
+    )
+    .await
+    .map_err(|e| OfframpError::Aligned(format!("{:?}", e)))?;
+
+    println!(
+        "Proof submitted and verified successfully on batch {}",
+        hex::encode(aligned_verification_data.batch_merkle_root)
+    );
+
+    Ok(Some(aligned_verification_data))
+}
+
+/// What Aligned answers for `verification_data` verified alone in a batch:
+/// the batch root is its leaf and the merkle path is empty.
+fn mock_aligned_verification_data(
+    verification_data: VerificationData,
+) -> Result<AlignedVerificationData, OfframpError> {
+    let commitment = VerificationDataCommitment::from(verification_data);
+    let leaf = commitment_hash(&commitment);
+
+    // The inclusion proof is a lambdaworks type, built through serde rather
+    // than depending on lambdaworks.
+    serde_json::from_value(serde_json::json!({
+        "verification_data_commitment": commitment,
+        "batch_merkle_root": leaf,
+        "batch_inclusion_proof": { "merkle_path": [] },
+        "index_in_batch": 0,
+    }))
+    .map_err(|e| OfframpError::Schema(e.to_string()))
+}
+
+/// Checks with `eth_call`s that `fillOfframp` would accept the public values
+/// of `proof`, so Aligned is not paid for a proof that can't be claimed.
+pub async fn preflight<M: Middleware>(
+    contract: &JackRampContract<M>,
+    proof: &SP1ProofWithPublicValues,
+) -> Result<(), OfframpError> {
+    let items = decode_public_values(proof.public_values.as_slice())
+        .map_err(|e| OfframpError::Schema(e.to_string()))?;
+
+    for item in items {
+        let request_offramp_id = H256::from_uint(&request_offramp_id(&item.offrampRequestParams));
+        let state = OfframpRequestState::fetch(contract, request_offramp_id).await?;
+        if !state.exists() {
+            return Err(OfframpError::WouldRevert(
+                JackRampContractErrors::OfframpRequestDoesNotExist(OfframpRequestDoesNotExist),
+            ));
+        }
+        if state.is_proved {
+            return Err(OfframpError::WouldRevert(
+                JackRampContractErrors::OfframpRequestAlreadyProved(OfframpRequestAlreadyProved),
+            ));
+        }
+
+        let hashed_claim_info = item.proof.hashedClaimInfo.0;
+        let used = contract
+            .used_reclaim_proofs(hashed_claim_info)
+            .call()
+            .await
+            .map_err(OfframpError::from_contract_error)?;
+        if used {
+            return Err(OfframpError::WouldRevert(
+                JackRampContractErrors::ReclaimProofAlreadyUsed(ReclaimProofAlreadyUsed {
+                    hashed_claim_info,
+                }),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Key of a proof in `usedProofs`: the hash of its Aligned verification data
+/// commitment, as `fillOfframp` computes it.
+pub fn proof_hash(aligned_verification_data: &AlignedVerificationData) -> [u8; 32] {
+    commitment_hash(&aligned_verification_data.verification_data_commitment)
+}
+
+/// The hash Aligned uses as the batch merkle tree leaf of a commitment.
+fn commitment_hash(commitment: &VerificationDataCommitment) -> [u8; 32] {
+    ethers::utils::keccak256(
+        [
+            commitment.proof_commitment.as_slice(),
+            commitment.pub_input_commitment.as_slice(),
+            commitment.proving_system_aux_data_commitment.as_slice(),
+            commitment.proof_generator_addr.as_slice(),
+        ]
+        .concat(),
+    )
+}
+
+/// Claims the stablecoins of a proof Aligned verified through `fillOfframp`.
+/// Batch proofs go through `fillOfframpBatch`, which the deployed contract
+/// lacks, so they are refused unless `batch_settlement` is set. The
+/// verification data of mock proofs can only be stubbed, so they are refused
+/// unless `mock` is set. Returns the hash of the claim transaction.
+pub async fn claim(
+    aligned_verification_data: &AlignedVerificationData,
+    signer: EvmSigner,
+    jackramp_contract_addr: &Address,
+    proof: &SP1ProofWithPublicValues,
+    batch_settlement: bool,
+    mock: bool,
+) -> Result<TxHash, OfframpError> {
+    if !mock && is_mock_proof(proof) {
+        return Err(OfframpError::Input(String::from(
+            "the proof is a mock proof, whose Aligned verification data is stubbed: pass --mock to claim it against a stubbed Aligned service manager",
+        )));
+    }
+
+    let pub_values = proof.public_values.as_slice();
+    let batch = is_batch_public_values(pub_values);
+    if batch && !batch_settlement {
+        return Err(OfframpError::Input(String::from(
+            "the proof fills a batch, which only a contract with fillOfframpBatch settles: pass --batch-settlement to claim it there",
+        )));
+    }
+
+    println!("Claiming Stablecoins...");
+
+    claim_stablecoin_with_verified_proof(
+        aligned_verification_data,
+        signer,
+        jackramp_contract_addr,
+        pub_values,
+        batch,
+    )
+    .await
+}
+
+async fn claim_stablecoin_with_verified_proof(
+    aligned_verification_data: &AlignedVerificationData,
+    signer: EvmSigner,
+    jackramp_contract_addr: &Address,
+    pub_values: &[u8],
+    batch: bool,
+) -> Result<TxHash, OfframpError> {
+    let signer = Arc::new(signer);
+    let index_in_batch = U256::from(aligned_verification_data.index_in_batch);
+    let ver_data_flattened_bytes: Vec<u8> = aligned_verification_data
+        .batch_inclusion_proof
+        .merkle_path
+        .as_slice()
+        .iter()
+        .flat_map(|array| array.to_vec())
+        .collect();
+
+    let merkle_path = Bytes::from(ver_data_flattened_bytes);
+    let commitment = &aligned_verification_data.verification_data_commitment;
+    let pub_values = Bytes::from(pub_values.to_vec());
+
+    let call = if batch {
+        JackRampBatchContract::new(*jackramp_contract_addr, signer).fill_offramp_batch(
+            commitment.proof_commitment,
+            commitment.pub_input_commitment,
+            commitment.proving_system_aux_data_commitment,
+            commitment.proof_generator_addr,
+            aligned_verification_data.batch_merkle_root,
+            merkle_path,
+            index_in_batch,
+            pub_values,
+        )
+    } else {
+        JackRampContract::new(*jackramp_contract_addr, signer).fill_offramp(
+            commitment.proof_commitment,
+            commitment.pub_input_commitment,
+            commitment.proving_system_aux_data_commitment,
+            commitment.proof_generator_addr,
+            aligned_verification_data.batch_merkle_root,
+            merkle_path,
+            index_in_batch,
+            pub_values,
+        )
+    };
+
+    let receipt = call
+        .send()
+        .await
+        .map_err(OfframpError::from_contract_error)?
+        .await
+        .map_err(|e| OfframpError::Contract(format!("Failed to submit tx {}", e)))?;
+
+    match receipt {
+        Some(receipt) => {
+            println!(
+                "Stablecoin claimed successfully. Transaction hash: {:x}",
+                receipt.transaction_hash
+            );
+            Ok(receipt.transaction_hash)
+        }
+        None => Err(OfframpError::Contract(String::from(
+            "Failed to claim stablecoin: no receipt",
+        ))),
+    }
+}
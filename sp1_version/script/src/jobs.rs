@@ -0,0 +1,626 @@
+use alloy_sol_types::SolType;
+use ethers::abi::{AbiDecode, AbiEncode};
+use ethers::types::{Address, BigEndianHash, H256};
+use rand::Rng;
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSqlOutput, Type, ValueRef};
+use rusqlite::{params, Connection, OptionalExtension, Row, ToSql};
+use serde::{Deserialize, Serialize};
+use sp1_sdk::{ProverClient, SP1ProvingKey};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use zktransfer_lib::PublicValuesStruct;
+
+use crate::artifact::save_sp1_proof;
+use crate::contract::OfframpRequestParams;
+use crate::error::OfframpError;
+use crate::{build_stdin, prover_client, request_offramp_id, types};
+
+/// How far a job goes: execute the program, prove it, or also settle it
+/// through Aligned and `fillOfframp`.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum JobMode {
+    #[default]
+    Execute,
+    Prove,
+    Claim,
+}
+
+impl JobMode {
+    /// The stage at which a job in this mode is done.
+    pub fn target(self) -> Stage {
+        match self {
+            JobMode::Execute => Stage::Executed,
+            JobMode::Prove => Stage::Proved,
+            JobMode::Claim => Stage::Claimed,
+        }
+    }
+}
+
+/// The last stage an offramp completed. A job is resumed from here after a
+/// crash, so each stage is only recorded once its artifacts are on disk. A
+/// failed job keeps its last completed stage in [`Job::last_stage`] and is
+/// resumed from there by [`JobStore::retry_failed`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    /// The Reclaim proof is stored and the job waits for a worker.
+    Fetched,
+    /// The program ran; cycles and public values are known.
+    Executed,
+    /// The SP1 proof is written to [`JobStore::proof_path`].
+    Proved,
+    /// Aligned verified the proof; the verification data is written to
+    /// [`JobStore::aligned_data_path`].
+    Submitted,
+    /// `fillOfframp` went through.
+    Claimed,
+    Failed,
+}
+
+impl Stage {
+    fn as_str(self) -> &'static str {
+        match self {
+            Stage::Fetched => "fetched",
+            Stage::Executed => "executed",
+            Stage::Proved => "proved",
+            Stage::Submitted => "submitted",
+            Stage::Claimed => "claimed",
+            Stage::Failed => "failed",
+        }
+    }
+}
+
+impl ToSql for Stage {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl FromSql for Stage {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_str()? {
+            "fetched" => Ok(Stage::Fetched),
+            "executed" => Ok(Stage::Executed),
+            "proved" => Ok(Stage::Proved),
+            "submitted" => Ok(Stage::Submitted),
+            "claimed" => Ok(Stage::Claimed),
+            "failed" => Ok(Stage::Failed),
+            stage => Err(FromSqlError::Other(
+                format!("unknown job stage {}", stage).into(),
+            )),
+        }
+    }
+}
+
+/// An offramp to execute or prove, as submitted to the proving server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JobRequest {
+    /// Reclaim proof of the bank transfer.
+    pub proof: types::Proof,
+    /// Address committed as the offramp `user`. Must be the `contextAddress`
+    /// of the proof, and the requester when filling a `RequestOfframp`.
+    pub recipient: String,
+    pub channel_id: String,
+    pub transaction_id: String,
+    pub request_timestamp: u32,
+    pub rate_quote: types::RateQuote,
+    #[serde(default)]
+    pub mode: JobMode,
+    /// The `requestOfframpId` the job must fill. Execution fails when the
+    /// public values file another request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_offramp_id: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Job {
+    pub id: String,
+    pub stage: Stage,
+    /// The last stage the job completed, which `stage` only differs from once
+    /// the job failed.
+    pub last_stage: Stage,
+    #[serde(skip)]
+    pub request: JobRequest,
+    pub mode: JobMode,
+    pub cycles: Option<u64>,
+    #[serde(serialize_with = "serialize_hex")]
+    pub public_values: Option<Vec<u8>>,
+    pub request_offramp_id: Option<String>,
+    pub batch_merkle_root: Option<String>,
+    pub tx_hash: Option<String>,
+    pub error: Option<String>,
+}
+
+impl Job {
+    /// Whether the job reached its target stage or failed.
+    pub fn is_finished(&self) -> bool {
+        self.stage == self.mode.target() || self.stage == Stage::Failed
+    }
+
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        let request: String = row.get("request")?;
+        let request: JobRequest = serde_json::from_str(&request)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(1, Type::Text, Box::new(e)))?;
+        let cycles: Option<i64> = row.get("cycles")?;
+        let stage: Stage = row.get("stage")?;
+        // Jobs that failed before `last_stage` was recorded start over.
+        let last_stage: Option<Stage> = row.get("last_stage")?;
+        let last_stage = match (last_stage, stage) {
+            (Some(last_stage), _) => last_stage,
+            (None, Stage::Failed) => Stage::Fetched,
+            (None, stage) => stage,
+        };
+
+        Ok(Job {
+            id: row.get("id")?,
+            stage,
+            last_stage,
+            mode: request.mode,
+            request,
+            cycles: cycles.map(|cycles| cycles as u64),
+            public_values: row.get("public_values")?,
+            request_offramp_id: row.get("request_offramp_id")?,
+            batch_merkle_root: row.get("batch_merkle_root")?,
+            tx_hash: row.get("tx_hash")?,
+            error: row.get("error")?,
+        })
+    }
+}
+
+/// A `RequestOfframp` the solver waits to fill a bank transfer for.
+#[derive(Debug, Clone)]
+pub struct OpenRequest {
+    pub request_offramp_id: H256,
+    pub params: OfframpRequestParams,
+    pub channel_id: String,
+    /// Timestamp of the block the request was made in.
+    pub requested_at: u32,
+    /// Why the last attempt to fill the request failed.
+    pub error: Option<String>,
+}
+
+impl OpenRequest {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        let request_offramp_id: String = row.get("request_offramp_id")?;
+        let request_offramp_id = request_offramp_id
+            .parse::<H256>()
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, Type::Text, Box::new(e)))?;
+        let params: Vec<u8> = row.get("params")?;
+        let params = OfframpRequestParams::decode(params)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(1, Type::Blob, Box::new(e)))?;
+
+        Ok(OpenRequest {
+            request_offramp_id,
+            params,
+            channel_id: row.get("channel_id")?,
+            requested_at: row.get("requested_at")?,
+            error: row.get("error")?,
+        })
+    }
+}
+
+fn serialize_hex<S: serde::Serializer>(
+    bytes: &Option<Vec<u8>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match bytes {
+        Some(bytes) => serializer.serialize_some(&format!("0x{}", hex::encode(bytes))),
+        None => serializer.serialize_none(),
+    }
+}
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS jobs (
+    id TEXT PRIMARY KEY,
+    request TEXT NOT NULL,
+    stage TEXT NOT NULL,
+    last_stage TEXT,
+    cycles INTEGER,
+    public_values BLOB,
+    request_offramp_id TEXT,
+    batch_merkle_root TEXT,
+    tx_hash TEXT,
+    error TEXT,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS jobs_stage ON jobs (stage);
+CREATE TABLE IF NOT EXISTS open_requests (
+    request_offramp_id TEXT PRIMARY KEY,
+    params BLOB NOT NULL,
+    channel_id TEXT NOT NULL,
+    requested_at INTEGER NOT NULL,
+    error TEXT
+);
+CREATE TABLE IF NOT EXISTS cursors (
+    name TEXT PRIMARY KEY,
+    next_block INTEGER NOT NULL
+);
+";
+
+/// Offramp jobs and the stage each one reached, kept in a SQLite database
+/// under `data_dir` next to the proofs and Aligned verification data, so a
+/// restarted service picks up where it crashed.
+pub struct JobStore {
+    connection: Mutex<Connection>,
+    data_dir: PathBuf,
+}
+
+impl JobStore {
+    /// Opens or creates `jobs.sqlite` in `data_dir`.
+    pub fn open(data_dir: &Path) -> Result<Self, OfframpError> {
+        std::fs::create_dir_all(data_dir)?;
+
+        let connection = Connection::open(data_dir.join("jobs.sqlite"))?;
+        connection.execute_batch(SCHEMA)?;
+        // Columns added since the first release of the store.
+        add_missing_column(&connection, "jobs", "last_stage")?;
+        add_missing_column(&connection, "open_requests", "error")?;
+
+        Ok(JobStore {
+            connection: Mutex::new(connection),
+            data_dir: data_dir.to_path_buf(),
+        })
+    }
+
+    /// Stores `request` under a new random id, at the `fetched` stage.
+    pub fn insert(&self, request: JobRequest) -> Result<Job, OfframpError> {
+        let id = hex::encode(rand::thread_rng().gen::<[u8; 16]>());
+        let raw_request =
+            serde_json::to_string(&request).map_err(|e| OfframpError::Schema(e.to_string()))?;
+        let now = now();
+
+        self.connection.lock().unwrap().execute(
+            "INSERT INTO jobs (id, request, stage, last_stage, request_offramp_id, created_at, updated_at) VALUES (?1, ?2, ?3, ?3, ?4, ?5, ?5)",
+            params![id, raw_request, Stage::Fetched, request.request_offramp_id, now],
+        )?;
+
+        Ok(Job {
+            id,
+            stage: Stage::Fetched,
+            last_stage: Stage::Fetched,
+            mode: request.mode,
+            cycles: None,
+            public_values: None,
+            request_offramp_id: request.request_offramp_id.clone(),
+            batch_merkle_root: None,
+            tx_hash: None,
+            error: None,
+            request,
+        })
+    }
+
+    pub fn get(&self, id: &str) -> Result<Option<Job>, OfframpError> {
+        Ok(self
+            .connection
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT * FROM jobs WHERE id = ?1",
+                params![id],
+                Job::from_row,
+            )
+            .optional()?)
+    }
+
+    /// The job filling `request_offramp_id`, if any.
+    pub fn find_by_request_offramp_id(
+        &self,
+        request_offramp_id: &str,
+    ) -> Result<Option<Job>, OfframpError> {
+        Ok(self
+            .connection
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT * FROM jobs WHERE request_offramp_id = lower(?1) ORDER BY created_at DESC",
+                params![request_offramp_id],
+                Job::from_row,
+            )
+            .optional()?)
+    }
+
+    /// The job proving bank transaction `transaction_id` on `channel_id`, if
+    /// any, so one transfer never fills two requests.
+    pub fn find_by_transaction(
+        &self,
+        channel_id: &str,
+        transaction_id: &str,
+    ) -> Result<Option<Job>, OfframpError> {
+        Ok(self
+            .connection
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT * FROM jobs WHERE lower(json_extract(request, '$.channel_id')) = lower(?1) AND json_extract(request, '$.transaction_id') = ?2 ORDER BY created_at DESC",
+                params![channel_id, transaction_id],
+                Job::from_row,
+            )
+            .optional()?)
+    }
+
+    /// Jobs a crash interrupted before their target stage, oldest first.
+    pub fn unfinished(&self) -> Result<Vec<Job>, OfframpError> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(
+            "SELECT * FROM jobs WHERE stage NOT IN ('claimed', 'failed') ORDER BY created_at",
+        )?;
+        let jobs = statement
+            .query_map([], Job::from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(jobs.into_iter().filter(|job| !job.is_finished()).collect())
+    }
+
+    pub fn record_execution(
+        &self,
+        id: &str,
+        cycles: u64,
+        public_values: &[u8],
+        request_offramp_id: &str,
+    ) -> Result<(), OfframpError> {
+        self.connection.lock().unwrap().execute(
+            "UPDATE jobs SET stage = ?2, last_stage = ?2, cycles = ?3, public_values = ?4, request_offramp_id = lower(?5), updated_at = ?6 WHERE id = ?1",
+            params![id, Stage::Executed, cycles as i64, public_values, request_offramp_id, now()],
+        )?;
+        Ok(())
+    }
+
+    /// Records the proof written to [`JobStore::proof_path`].
+    pub fn record_proof(&self, id: &str) -> Result<(), OfframpError> {
+        self.set_stage(id, Stage::Proved)
+    }
+
+    /// Records the Aligned batch the proof was verified in. The verification
+    /// data must already be written to [`JobStore::aligned_data_path`].
+    pub fn record_submission(&self, id: &str, batch_merkle_root: &str) -> Result<(), OfframpError> {
+        self.connection.lock().unwrap().execute(
+            "UPDATE jobs SET stage = ?2, last_stage = ?2, batch_merkle_root = ?3, updated_at = ?4 WHERE id = ?1",
+            params![id, Stage::Submitted, batch_merkle_root, now()],
+        )?;
+        Ok(())
+    }
+
+    /// Records the `fillOfframp` transaction, `None` when the fill is only
+    /// known from `usedProofs`.
+    pub fn record_claim(&self, id: &str, tx_hash: Option<&str>) -> Result<(), OfframpError> {
+        self.connection.lock().unwrap().execute(
+            "UPDATE jobs SET stage = ?2, last_stage = ?2, tx_hash = ?3, updated_at = ?4 WHERE id = ?1",
+            params![id, Stage::Claimed, tx_hash, now()],
+        )?;
+        Ok(())
+    }
+
+    /// Marks the job failed with `error`, keeping its last completed stage.
+    pub fn record_failure(&self, id: &str, error: &str) -> Result<(), OfframpError> {
+        self.connection.lock().unwrap().execute(
+            "UPDATE jobs SET stage = ?2, error = ?3, updated_at = ?4 WHERE id = ?1",
+            params![id, Stage::Failed, error, now()],
+        )?;
+        Ok(())
+    }
+
+    /// Puts every failed job back at its last completed stage, so it is
+    /// resumed from there instead of starting over, and returns how many
+    /// were.
+    pub fn retry_failed(&self) -> Result<usize, OfframpError> {
+        Ok(self.connection.lock().unwrap().execute(
+            "UPDATE jobs SET stage = coalesce(last_stage, ?1), error = NULL, updated_at = ?3 WHERE stage = ?2",
+            params![Stage::Fetched, Stage::Failed, now()],
+        )?)
+    }
+
+    fn set_stage(&self, id: &str, stage: Stage) -> Result<(), OfframpError> {
+        self.connection.lock().unwrap().execute(
+            "UPDATE jobs SET stage = ?2, last_stage = ?2, updated_at = ?3 WHERE id = ?1",
+            params![id, stage, now()],
+        )?;
+        Ok(())
+    }
+
+    /// Requests the solver saw and has not started a fill for, oldest first.
+    pub fn open_requests(&self) -> Result<Vec<OpenRequest>, OfframpError> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement =
+            connection.prepare("SELECT * FROM open_requests ORDER BY requested_at")?;
+        let requests = statement
+            .query_map([], OpenRequest::from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(requests)
+    }
+
+    pub fn insert_open_request(&self, request: &OpenRequest) -> Result<(), OfframpError> {
+        self.connection.lock().unwrap().execute(
+            "INSERT OR REPLACE INTO open_requests (request_offramp_id, params, channel_id, requested_at, error) VALUES (lower(?1), ?2, ?3, ?4, ?5)",
+            params![
+                format!("{:#x}", request.request_offramp_id),
+                request.params.clone().encode(),
+                request.channel_id,
+                request.requested_at,
+                request.error
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Records why filling the open request `request_offramp_id` failed. The
+    /// request stays open and is tried again on the next poll.
+    pub fn record_open_request_failure(
+        &self,
+        request_offramp_id: &H256,
+        error: &str,
+    ) -> Result<(), OfframpError> {
+        self.connection.lock().unwrap().execute(
+            "UPDATE open_requests SET error = ?2 WHERE request_offramp_id = lower(?1)",
+            params![format!("{:#x}", request_offramp_id), error],
+        )?;
+        Ok(())
+    }
+
+    pub fn remove_open_request(&self, request_offramp_id: &H256) -> Result<(), OfframpError> {
+        self.connection.lock().unwrap().execute(
+            "DELETE FROM open_requests WHERE request_offramp_id = lower(?1)",
+            params![format!("{:#x}", request_offramp_id)],
+        )?;
+        Ok(())
+    }
+
+    /// The next block to read the logs named `name` from, once
+    /// [`JobStore::set_next_block`] recorded one.
+    pub fn next_block(&self, name: &str) -> Result<Option<u64>, OfframpError> {
+        let next_block: Option<i64> = self
+            .connection
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT next_block FROM cursors WHERE name = ?1",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(next_block.map(|block| block as u64))
+    }
+
+    pub fn set_next_block(&self, name: &str, next_block: u64) -> Result<(), OfframpError> {
+        self.connection.lock().unwrap().execute(
+            "INSERT OR REPLACE INTO cursors (name, next_block) VALUES (?1, ?2)",
+            params![name, next_block as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Where the bincode SP1 proof of job `id` is written.
+    pub fn proof_path(&self, id: &str) -> PathBuf {
+        self.data_dir.join(format!("{}.bin", id))
+    }
+
+    /// Where the Aligned verification data of job `id` is written.
+    pub fn aligned_data_path(&self, id: &str) -> PathBuf {
+        self.data_dir.join(format!("{}.aligned.json", id))
+    }
+}
+
+/// Executes and proves jobs, recording every completed stage in the store.
+pub struct JobProver {
+    client: ProverClient,
+    pk: SP1ProvingKey,
+    elf: &'static [u8],
+    witnesses: Vec<Address>,
+    oracles: Vec<Address>,
+    max_transfer_age: u32,
+}
+
+impl JobProver {
+    pub fn new(
+        elf: &'static [u8],
+        witnesses: Vec<Address>,
+        oracles: Vec<Address>,
+        max_transfer_age: u32,
+        mock: bool,
+    ) -> Self {
+        let client = prover_client(mock);
+        let (pk, _) = client.setup(elf);
+
+        JobProver {
+            client,
+            pk,
+            elf,
+            witnesses,
+            oracles,
+            max_transfer_age,
+        }
+    }
+
+    /// Brings `job` to the `executed` stage, or `proved` unless it is an
+    /// execute job, skipping the stages it already completed.
+    pub fn run(&self, store: &JobStore, job: &Job) -> Result<(), OfframpError> {
+        let request = &job.request;
+
+        let inputs = vec![types::OfframpInput {
+            proof: serde_json::to_string(&request.proof)
+                .map_err(|e| OfframpError::Schema(e.to_string()))?,
+            transaction_id: request.transaction_id.clone(),
+            channel_id: request.channel_id.clone(),
+            request_timestamp: request.request_timestamp,
+        }];
+        let stdin = build_stdin(
+            &self.witnesses,
+            &self.oracles,
+            &request.rate_quote,
+            self.max_transfer_age,
+            &inputs,
+        );
+
+        if job.stage == Stage::Fetched {
+            let (output, report) = self
+                .client
+                .execute(self.elf, stdin.clone())
+                .run()
+                .map_err(|e| OfframpError::Execution(e.to_string()))?;
+            let decoded = PublicValuesStruct::abi_decode(output.as_slice(), true)
+                .map_err(|e| OfframpError::Schema(e.to_string()))?;
+
+            let filled_request_id = format!(
+                "{:#x}",
+                H256::from_uint(&request_offramp_id(&decoded.offrampRequestParams))
+            );
+            if let Some(expected) = &request.request_offramp_id {
+                if !expected.eq_ignore_ascii_case(&filled_request_id) {
+                    return Err(OfframpError::Input(format!(
+                        "the transfer fills offramp request {}, not {}",
+                        filled_request_id, expected
+                    )));
+                }
+            }
+
+            store.record_execution(
+                &job.id,
+                report.total_instruction_count(),
+                output.as_slice(),
+                &filled_request_id,
+            )?;
+        }
+
+        if job.mode != JobMode::Execute && matches!(job.stage, Stage::Fetched | Stage::Executed) {
+            let proof = self
+                .client
+                .prove(&self.pk, stdin)
+                .run()
+                .map_err(|e| OfframpError::Proving(e.to_string()))?;
+            save_sp1_proof(&store.proof_path(&job.id), &proof)?;
+            store.record_proof(&job.id)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Adds the nullable text `column` to `table` of a database created before
+/// the column existed.
+fn add_missing_column(
+    connection: &Connection,
+    table: &str,
+    column: &str,
+) -> Result<(), OfframpError> {
+    let exists: bool = connection.query_row(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info(?1) WHERE name = ?2",
+        params![table, column],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        connection.execute_batch(&format!("ALTER TABLE {} ADD COLUMN {} TEXT", table, column))?;
+    }
+
+    Ok(())
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or_default()
+}
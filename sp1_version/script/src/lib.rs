@@ -1 +1,117 @@
-pub mod types;
+pub mod artifact;
+pub mod attestor;
+pub mod bank;
+pub mod config;
+pub mod contract;
+pub mod error;
+pub mod evm;
+pub mod jobs;
+pub mod onchain;
+pub mod revert;
+pub mod solver;
+pub mod status;
+
+pub use error::OfframpError;
+pub use zktransfer_lib::types;
+
+use alloy_sol_types::SolType;
+use ethers::types::{Address, U256};
+use ethers::utils::keccak256;
+use sp1_sdk::{ProverClient, SP1Proof, SP1ProofWithPublicValues, SP1Stdin};
+use std::io;
+use zktransfer_lib::{rate, Amount, OfframpRequestParams};
+
+/// Address of the Reclaim witness that signs zkFetch claims.
+pub const DEFAULT_RECLAIM_WITNESS: &str = "0x244897572368eadf65bfbc5aec98d8e5443a9072";
+
+/// Default number of seconds a bank transfer may precede its Reclaim claim.
+pub const DEFAULT_MAX_TRANSFER_AGE: u32 = 24 * 60 * 60;
+
+/// Reads a signed exchange rate quote from a JSON file.
+pub fn load_rate_quote(path: &str) -> Result<types::RateQuote, OfframpError> {
+    let raw_quote = std::fs::read_to_string(path)?;
+    serde_json::from_str(&raw_quote).map_err(|error| {
+        OfframpError::Input(format!("invalid rate quote file {}: {}", path, error))
+    })
+}
+
+/// Prints `question` and reads a trimmed answer from stdin.
+pub fn read_answer(question: &str) -> Result<String, OfframpError> {
+    println!("{}", question);
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+
+    Ok(String::from(answer.trim()))
+}
+
+/// Parses the unix timestamp at which an offramp was requested.
+pub fn parse_request_timestamp(raw_timestamp: &str) -> Result<u32, OfframpError> {
+    raw_timestamp
+        .parse()
+        .map_err(|_| OfframpError::Input(format!("invalid request timestamp {}", raw_timestamp)))
+}
+
+/// Writes the program inputs in the order the zkVM program reads them.
+pub fn build_stdin(
+    witnesses: &[Address],
+    oracles: &[Address],
+    quote: &types::RateQuote,
+    max_transfer_age: u32,
+    inputs: &[types::OfframpInput],
+) -> SP1Stdin {
+    let mut stdin = SP1Stdin::new();
+
+    let witnesses: Vec<[u8; 20]> = witnesses.iter().map(|w| w.to_fixed_bytes()).collect();
+    stdin.write(&witnesses);
+
+    let oracles: Vec<[u8; 20]> = oracles.iter().map(|o| o.to_fixed_bytes()).collect();
+    stdin.write(&oracles);
+    stdin.write(quote);
+    stdin.write(&max_transfer_age);
+    stdin.write(&inputs);
+
+    stdin
+}
+
+/// The SP1 prover, or with `mock` one that only executes the program and
+/// returns proofs holding its real public values.
+pub fn prover_client(mock: bool) -> ProverClient {
+    if mock {
+        ProverClient::mock()
+    } else {
+        ProverClient::new()
+    }
+}
+
+/// Whether `proof` came from the mock prover, which leaves the proof itself
+/// empty.
+pub fn is_mock_proof(proof: &SP1ProofWithPublicValues) -> bool {
+    match &proof.proof {
+        SP1Proof::Core(shard_proofs) => shard_proofs.is_empty(),
+        SP1Proof::Plonk(plonk) => plonk.raw_proof.is_empty(),
+        _ => false,
+    }
+}
+
+/// The id `JackRampContract` files an offramp request under, i.e. the
+/// keccak256 hash of the ABI encoded `OfframpRequestParams`.
+pub fn request_offramp_id(params: &OfframpRequestParams) -> U256 {
+    U256::from(keccak256(OfframpRequestParams::abi_encode(params)))
+}
+
+/// Prices a real world amount in token units with `quote`, through the same
+/// [`rate::convert`] as the zkVM program, so a request's `amount` matches
+/// the one its fill commits.
+pub fn price(amount_real_world: Amount, quote: &types::RateQuote) -> Result<U256, OfframpError> {
+    rate::convert(
+        alloy_primitives::U256::from(amount_real_world.minor_units()),
+        quote,
+    )
+    .map(|amount| U256(amount.into_limbs()))
+    .ok_or_else(|| {
+        OfframpError::Input(format!(
+            "{} priced with {} decimals overflows uint256",
+            amount_real_world, quote.decimals
+        ))
+    })
+}
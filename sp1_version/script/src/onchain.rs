@@ -0,0 +1,182 @@
+use ethers::prelude::*;
+use ethers::types::{Address, Bytes};
+use serde::{Deserialize, Serialize};
+use sp1_sdk::{HashableKey, SP1Proof, SP1ProofWithPublicValues, SP1VerifyingKey};
+use std::sync::Arc;
+use zktransfer_lib::is_batch_public_values;
+
+use crate::contract::{JackRampGatewayContract, SP1VerifierGateway};
+use crate::error::OfframpError;
+use crate::evm::EvmSigner;
+use crate::revert::describe_verifier_revert;
+
+/// How a proof reaches `JackRampContract`, picked per run.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Settlement {
+    /// Verified in an Aligned batch, then claimed with `fillOfframp`.
+    #[default]
+    Aligned,
+    /// Wrapped in PLONK and verified by the SP1 verifier gateway within
+    /// `fillOfframpWithSp1Proof`. No Aligned deposit or batcher needed.
+    Plonk,
+}
+
+/// The program verifying key the SP1 verifier checks proofs against.
+pub fn program_vkey(vk: &SP1VerifyingKey) -> Result<[u8; 32], OfframpError> {
+    let bytes = hex::decode(vk.bytes32().trim_start_matches("0x"))
+        .map_err(|e| OfframpError::Verification(e.to_string()))?;
+
+    bytes
+        .try_into()
+        .map_err(|_| OfframpError::Verification(String::from("program vkey is not 32 bytes")))
+}
+
+/// The `proofBytes` of `proof` for `verifyProof`.
+pub fn plonk_proof_bytes(proof: &SP1ProofWithPublicValues) -> Result<Bytes, OfframpError> {
+    match &proof.proof {
+        SP1Proof::Plonk(plonk) => encode_plonk_proof(&plonk.plonk_vkey_hash, &plonk.encoded_proof),
+        _ => Err(OfframpError::Input(String::from(
+            "the SP1 proof is not PLONK wrapped, prove it with `--settlement plonk`",
+        ))),
+    }
+}
+
+/// `proofBytes` as the SP1 verifier reads them: the first 4 bytes of the PLONK
+/// verifying key hash, which the gateway routes on and the verifier checks
+/// against its `VERIFIER_HASH`, then the encoded proof.
+pub fn encode_plonk_proof(
+    plonk_vkey_hash: &[u8; 32],
+    encoded_proof: &str,
+) -> Result<Bytes, OfframpError> {
+    let encoded_proof = hex::decode(encoded_proof.trim_start_matches("0x"))
+        .map_err(|e| OfframpError::Schema(e.to_string()))?;
+
+    Ok(Bytes::from(
+        [&plonk_vkey_hash[..4], &encoded_proof[..]].concat(),
+    ))
+}
+
+/// A PLONK proof with the arguments `verifyProof` takes it with, as hex, in
+/// the layout of the SP1 contract fixtures.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlonkFixture {
+    pub vkey: String,
+    pub public_values: String,
+    pub proof: String,
+}
+
+/// The fixture of a PLONK wrapped proof of the program with verifying key `vk`.
+pub fn plonk_fixture(
+    proof: &SP1ProofWithPublicValues,
+    vk: &SP1VerifyingKey,
+) -> Result<PlonkFixture, OfframpError> {
+    Ok(PlonkFixture {
+        vkey: format!("0x{}", hex::encode(program_vkey(vk)?)),
+        public_values: format!("0x{}", hex::encode(proof.public_values.as_slice())),
+        proof: format!("0x{}", hex::encode(plonk_proof_bytes(proof)?)),
+    })
+}
+
+/// Verifies a PLONK proof with an `eth_call` to an SP1 verifier gateway.
+pub async fn verify_onchain<M: Middleware>(
+    gateway: &SP1VerifierGateway<M>,
+    program_vkey: [u8; 32],
+    public_values: Bytes,
+    proof_bytes: Bytes,
+) -> Result<(), OfframpError> {
+    gateway
+        .verify_proof(program_vkey, public_values, proof_bytes)
+        .call()
+        .await
+        .map_err(|e| {
+            let reason = e
+                .as_revert()
+                .and_then(|data| describe_verifier_revert(data))
+                .unwrap_or_else(|| e.to_string());
+            OfframpError::Verification(format!("the SP1 verifier rejected it: {}", reason))
+        })
+}
+
+/// Claims the stablecoins of a PLONK wrapped proof through the gateway
+/// variant of `JackRampContract`, after checking it against the gateway the
+/// contract verifies with. Returns the hash of the claim transaction.
+pub async fn claim_onchain(
+    signer: EvmSigner,
+    jackramp_contract_addr: &Address,
+    proof: &SP1ProofWithPublicValues,
+    batch_settlement: bool,
+) -> Result<TxHash, OfframpError> {
+    println!("Claiming Stablecoins...");
+
+    let signer = Arc::new(signer);
+    let jr_contract = JackRampGatewayContract::new(*jackramp_contract_addr, signer.clone());
+    let public_values = Bytes::from(proof.public_values.to_vec());
+    let proof_bytes = plonk_proof_bytes(proof)?;
+
+    let gateway = SP1VerifierGateway::new(
+        jr_contract
+            .verifier()
+            .call()
+            .await
+            .map_err(OfframpError::from_contract_error)?,
+        signer,
+    );
+    let program_vkey = jr_contract
+        .program_v_key()
+        .call()
+        .await
+        .map_err(OfframpError::from_contract_error)?;
+    verify_onchain(
+        &gateway,
+        program_vkey,
+        public_values.clone(),
+        proof_bytes.clone(),
+    )
+    .await?;
+
+    fill_with_sp1_proof(&jr_contract, public_values, proof_bytes, batch_settlement).await
+}
+
+/// Sends `fillOfframpWithSp1Proof`, or `fillOfframpBatchWithSp1Proof` for batch
+/// public values, which like `fillOfframpBatch` are refused unless
+/// `batch_settlement` is set.
+pub async fn fill_with_sp1_proof<M: Middleware>(
+    jr_contract: &JackRampGatewayContract<M>,
+    public_values: Bytes,
+    proof_bytes: Bytes,
+    batch_settlement: bool,
+) -> Result<TxHash, OfframpError> {
+    let batch = is_batch_public_values(&public_values);
+    if batch && !batch_settlement {
+        return Err(OfframpError::Input(String::from(
+            "the proof fills a batch, which only a contract with fillOfframpBatchWithSp1Proof settles: pass --batch-settlement to claim it there",
+        )));
+    }
+
+    let fill = if batch {
+        JackRampGatewayContract::fill_offramp_batch_with_sp_1_proof
+    } else {
+        JackRampGatewayContract::fill_offramp_with_sp_1_proof
+    };
+
+    let receipt = fill(jr_contract, public_values, proof_bytes)
+        .send()
+        .await
+        .map_err(OfframpError::from_contract_error)?
+        .await
+        .map_err(|e| OfframpError::Contract(format!("Failed to submit tx {}", e)))?;
+
+    match receipt {
+        Some(receipt) => {
+            println!(
+                "Stablecoin claimed successfully. Transaction hash: {:x}",
+                receipt.transaction_hash
+            );
+            Ok(receipt.transaction_hash)
+        }
+        None => Err(OfframpError::Contract(String::from(
+            "Failed to claim stablecoin: no receipt",
+        ))),
+    }
+}
@@ -0,0 +1,135 @@
+use ethers::contract::ContractRevert;
+use ethers::types::{H160, H256};
+
+use crate::contract::plonk_verifier::SP1VerifierPlonkErrors;
+use crate::contract::{JackRampContractErrors, SP1VerifierGatewayErrors};
+
+/// Decodes revert data into one of the custom errors of `JackRampContract`
+/// or a revert string, `None` when the selector is unknown.
+pub fn decode_revert(data: &[u8]) -> Option<JackRampContractErrors> {
+    JackRampContractErrors::decode_with_selector(data)
+}
+
+/// Names a decoded revert with its offending fields, and what it means for
+/// the offramp.
+pub fn describe_revert(revert: &JackRampContractErrors) -> String {
+    use JackRampContractErrors::*;
+
+    match revert {
+        ERC20InsufficientAllowance(e) => format!(
+            "ERC20InsufficientAllowance(spender: {}, allowance: {}, needed: {}): approve the contract first",
+            address(e.spender),
+            e.allowance,
+            e.needed
+        ),
+        ERC20InsufficientBalance(e) => format!(
+            "ERC20InsufficientBalance(sender: {}, balance: {}, needed: {}): not enough stablecoins",
+            address(e.sender),
+            e.balance,
+            e.needed
+        ),
+        ERC20InvalidApprover(e) => {
+            format!("ERC20InvalidApprover(approver: {})", address(e.approver))
+        }
+        ERC20InvalidReceiver(e) => {
+            format!("ERC20InvalidReceiver(receiver: {})", address(e.receiver))
+        }
+        ERC20InvalidSender(e) => format!("ERC20InvalidSender(sender: {})", address(e.sender)),
+        ERC20InvalidSpender(e) => {
+            format!("ERC20InvalidSpender(spender: {})", address(e.spender))
+        }
+        InvalidElf(e) => format!(
+            "InvalidElf(provingSystemAuxDataCommitment: {}): the proof was not made with the program the contract expects",
+            bytes32(e.proving_system_aux_data_commitment)
+        ),
+        InvalidReclaimProof(_) => String::from(
+            "InvalidReclaimProof: the Reclaim proof in the public values was rejected",
+        ),
+        OfframpRequestAlreadyExists(_) => String::from(
+            "OfframpRequestAlreadyExists: an identical offramp request is already open",
+        ),
+        OfframpRequestAlreadyProved(_) => String::from(
+            "OfframpRequestAlreadyProved: the offramp request was already filled",
+        ),
+        OfframpRequestAmountIsZero(_) => {
+            String::from("OfframpRequestAmountIsZero: the amount must not be zero")
+        }
+        OfframpRequestChannelAccountIsEmpty(_) => String::from(
+            "OfframpRequestChannelAccountIsEmpty: the channel account must not be empty",
+        ),
+        OfframpRequestChannelIdIsEmpty(_) => String::from(
+            "OfframpRequestChannelIdIsEmpty: the channel id must not be empty",
+        ),
+        OfframpRequestDoesNotExist(_) => String::from(
+            "OfframpRequestDoesNotExist: no offramp request matches the public values",
+        ),
+        ProofAlreadyUsed(e) => format!(
+            "ProofAlreadyUsed(fullHash: {}): the proof already filled a request",
+            bytes32(e.full_hash)
+        ),
+        ProofGeneratorAddrMismatch(_) => String::from(
+            "ProofGeneratorAddrMismatch: the proof was submitted to Aligned by another address",
+        ),
+        ProofNotIncludedInBatch(_) => String::from(
+            "ProofNotIncludedInBatch: Aligned has not verified the proof in this batch",
+        ),
+        PubInputCommitmentMismatch(_) => String::from(
+            "PubInputCommitmentMismatch: the public values are not the ones Aligned verified",
+        ),
+        ReclaimProofAlreadyUsed(e) => format!(
+            "ReclaimProofAlreadyUsed(hashedClaimInfo: {}): the bank transfer already filled a request",
+            bytes32(e.hashed_claim_info)
+        ),
+        ReentrancyGuardReentrantCall(_) => String::from("ReentrancyGuardReentrantCall"),
+        SafeERC20FailedOperation(e) => {
+            format!("SafeERC20FailedOperation(token: {})", address(e.token))
+        }
+        StaticCallFailed(_) => String::from(
+            "StaticCallFailed: the contract could not reach the Aligned service manager",
+        ),
+        RevertString(reason) => format!("revert: {}", reason),
+    }
+}
+
+/// Names a revert of the SP1 verifier gateway or of the PLONK verifier it
+/// routed the proof to, `None` when it is neither.
+pub fn describe_verifier_revert(data: &[u8]) -> Option<String> {
+    if let Some(revert) = SP1VerifierGatewayErrors::decode_with_selector(data) {
+        return match revert {
+            SP1VerifierGatewayErrors::RouteNotFound(e) => Some(format!(
+                "RouteNotFound(selector: {}): the gateway has no verifier for the SP1 version of the proof",
+                selector(e.selector)
+            )),
+            SP1VerifierGatewayErrors::RouteIsFrozen(e) => Some(format!(
+                "RouteIsFrozen(selector: {}): the verifier for the SP1 version of the proof was frozen",
+                selector(e.selector)
+            )),
+            SP1VerifierGatewayErrors::RevertString(reason) => Some(format!("revert: {}", reason)),
+            _ => None,
+        };
+    }
+
+    match SP1VerifierPlonkErrors::decode_with_selector(data)? {
+        SP1VerifierPlonkErrors::WrongVerifierSelector(e) => Some(format!(
+            "WrongVerifierSelector(received: {}, expected: {})",
+            selector(e.received),
+            selector(e.expected)
+        )),
+        SP1VerifierPlonkErrors::InvalidProof(_) => Some(String::from(
+            "InvalidProof: the proof does not verify for the program vkey and public values",
+        )),
+        SP1VerifierPlonkErrors::RevertString(reason) => Some(format!("revert: {}", reason)),
+    }
+}
+
+fn address(address: H160) -> String {
+    format!("{:#x}", address)
+}
+
+fn bytes32(bytes: [u8; 32]) -> String {
+    format!("{:#x}", H256(bytes))
+}
+
+fn selector(selector: [u8; 4]) -> String {
+    format!("0x{}", hex::encode(selector))
+}
@@ -0,0 +1,554 @@
+use clap::Parser;
+use ethers::providers::Middleware;
+use ethers::types::{BlockNumber, H160, H256, U256};
+use ethers::utils::keccak256;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use zktransfer_lib::banks::{hashed_channel_id, parse_transfer_period, CHANNEL_IDS};
+use zktransfer_lib::rate::MAX_RATE_AGE_S;
+use zktransfer_lib::Amount;
+
+use crate::artifact::{
+    load_aligned_verification_data, load_sp1_proof, save_aligned_verification_data,
+};
+use crate::attestor::{AttestorArgs, AttestorClient};
+use crate::bank::bank_provider;
+use crate::config::{Answers, ProgramArgs, PromptArgs};
+use crate::contract::{JackRampContract, OfframpRequestParams};
+use crate::error::OfframpError;
+use crate::evm::{claim, connect, preflight, proof_hash, submit, EvmSigner, WalletArgs};
+use crate::jobs::{Job, JobMode, JobProver, JobRequest, JobStore, OpenRequest, Stage};
+use crate::{load_rate_quote, price, types};
+
+/// Options of the `solver` binary.
+#[derive(Parser, Debug)]
+#[command(version, about = "Fill offramp requests as soon as the bank transfer shows up", long_about = None)]
+pub struct SolverArgs {
+    #[command(flatten)]
+    pub wallet: WalletArgs,
+    #[command(flatten)]
+    pub program: ProgramArgs,
+    #[arg(short, long, default_value = "wss://batcher.alignedlayer.com")]
+    pub batcher_url: String,
+    #[arg(short, long)]
+    pub jackramp_contract_address: H160,
+    /// Highest Aligned fee, in ETH, accepted for each fill.
+    #[arg(long, env = "JACKRAMP_MAX_FEE")]
+    pub max_fee: Option<String>,
+    /// Bank API listing the operator's outgoing transfers, in the response
+    /// shape of the `--transfers-channel-id` bank.
+    #[arg(long, env = "JACKRAMP_TRANSFERS_URL")]
+    pub transfers_url: String,
+    /// Bank the `--transfers-url` belongs to, which picks the parser of its
+    /// responses and is the channel of rows that don't name one.
+    #[arg(long, env = "JACKRAMP_TRANSFERS_CHANNEL_ID", default_value = "bca")]
+    pub transfers_channel_id: String,
+    /// Seconds between two polls of the chain and the bank.
+    #[arg(long, default_value_t = 15)]
+    pub poll_interval: u64,
+    /// Oracle endpoint answering `?pair=<PAIR>&timestampS=<NOW>` with a
+    /// signed quote, like the mock's `/api/v2/rate`. Queried on every poll;
+    /// without it the `--rate-quote` file is read again instead, for another
+    /// process to keep fresh.
+    #[arg(long, env = "JACKRAMP_RATE_URL")]
+    pub rate_url: Option<String>,
+    /// First block to look for `RequestOfframp` logs in. Defaults to the block
+    /// after the last one read into `--data-dir`, or the latest block on the
+    /// first run.
+    #[arg(long)]
+    pub from_block: Option<u64>,
+    /// Directory holding the job database, proofs and Aligned verification data.
+    #[arg(long, default_value = "solver")]
+    pub data_dir: PathBuf,
+    /// How far to take each fill. `execute` and `prove` stop before paying
+    /// Aligned, e.g. to try the solver against anvil and the mock bank.
+    #[arg(long, value_enum, default_value_t = JobMode::Claim)]
+    pub mode: JobMode,
+    #[command(flatten)]
+    pub attestor: AttestorArgs,
+    #[command(flatten)]
+    pub prompts: PromptArgs,
+}
+
+/// Seconds a fill may take between picking the rate quote and the attestor
+/// signing the claim it prices.
+const RATE_QUOTE_MARGIN_S: u32 = 5 * 60;
+
+/// Name of the `RequestOfframp` log cursor in the job store.
+const REQUEST_OFFRAMP_CURSOR: &str = "request_offramp";
+
+/// Watches `RequestOfframp` logs and fills each request once the operator's
+/// bank transfer for it shows up.
+pub struct Solver {
+    args: SolverArgs,
+    elf: &'static [u8],
+    answers: Answers,
+    authorization_token: String,
+    max_fee_ceiling: Option<U256>,
+    signer: EvmSigner,
+    contract: JackRampContract<EvmSigner>,
+    attestor: AttestorClient,
+    bank: reqwest::Client,
+    store: Arc<JobStore>,
+    prover: Arc<JobProver>,
+    rate_quote: types::RateQuote,
+    open: HashMap<H256, OpenRequest>,
+    next_block: u64,
+}
+
+impl Solver {
+    /// Connects the wallet and opens the job store of `args`, proving fills
+    /// of the program `elf`.
+    pub async fn new(args: SolverArgs, elf: &'static [u8]) -> Result<Self, OfframpError> {
+        // Fails on an unknown bank before anything is polled.
+        bank_provider(&args.transfers_channel_id)?;
+        let answers = Answers::load(args.prompts.clone())?;
+        let authorization_token = answers.authorization_token()?;
+        let max_fee_ceiling = answers.max_fee(args.max_fee.as_ref())?;
+        let signer = connect(&args.wallet, &answers).await?;
+        let contract =
+            JackRampContract::new(args.jackramp_contract_address, Arc::new(signer.clone()));
+
+        // Requests seen before a restart are kept in the store, and logs are
+        // read on from the last block read. Fills that failed are resumed
+        // from their last completed stage with the unfinished ones.
+        let store = JobStore::open(&args.data_dir)?;
+        let retried = store.retry_failed()?;
+        if retried > 0 {
+            println!("Retrying {} failed job(s)", retried);
+        }
+        let open = store
+            .open_requests()?
+            .into_iter()
+            .map(|request| (request.request_offramp_id, request))
+            .collect::<HashMap<H256, OpenRequest>>();
+        let next_block = match (args.from_block, store.next_block(REQUEST_OFFRAMP_CURSOR)?) {
+            (Some(block), _) | (None, Some(block)) => block,
+            (None, None) => signer
+                .get_block_number()
+                .await
+                .map_err(|e| OfframpError::Rpc(e.to_string()))?
+                .as_u64(),
+        };
+
+        Ok(Solver {
+            authorization_token,
+            max_fee_ceiling,
+            contract,
+            attestor: AttestorClient::new(&args.attestor)?,
+            bank: reqwest::Client::new(),
+            store: Arc::new(store),
+            prover: Arc::new(JobProver::new(
+                elf,
+                args.program.witnesses.clone(),
+                args.program.oracles.clone(),
+                args.program.max_transfer_age,
+                args.program.mock,
+            )),
+            rate_quote: load_rate_quote(&args.program.rate_quote)?,
+            open,
+            next_block,
+            signer,
+            answers,
+            args,
+            elf,
+        })
+    }
+
+    pub fn args(&self) -> &SolverArgs {
+        &self.args
+    }
+
+    pub fn address(&self) -> H160 {
+        self.signer.address()
+    }
+
+    pub fn store(&self) -> &JobStore {
+        &self.store
+    }
+
+    /// The first block the next poll reads `RequestOfframp` logs from.
+    pub fn next_block(&self) -> u64 {
+        self.next_block
+    }
+
+    /// Requests waiting for their bank transfer.
+    pub fn open_requests(&self) -> impl Iterator<Item = &OpenRequest> {
+        self.open.values()
+    }
+
+    /// Resumes unfinished fills, picks up new requests, then starts a fill
+    /// for every request whose bank transfer showed up.
+    pub async fn poll(&mut self) -> Result<(), OfframpError> {
+        for job in self.store.unfinished()? {
+            println!("Resuming job {} from stage {:?}", job.id, job.stage);
+            self.settle(&job).await;
+        }
+
+        let rate_quote = self.refresh_rate_quote().await?;
+        self.watch_requests().await?;
+        if self.open.is_empty() {
+            return Ok(());
+        }
+        let Some(rate_quote) = rate_quote else {
+            log::warn!(
+                "no rate quote within {}s of now, leaving {} requests open",
+                MAX_RATE_AGE_S - RATE_QUOTE_MARGIN_S,
+                self.open.len()
+            );
+            return Ok(());
+        };
+
+        let transfers = self.fetch_transfers().await?;
+        let request_ids: Vec<H256> = self.open.keys().copied().collect();
+        for request_id in request_ids {
+            let request = &self.open[&request_id];
+            // Requests are kept open whatever they ask, and priced again with
+            // every new quote.
+            if !prices_like(&request.params, &rate_quote) {
+                log::debug!(
+                    "request {:#x} doesn't price like the rate quote, leaving it open",
+                    request_id
+                );
+                continue;
+            }
+
+            let mut found = None;
+            for transaction in transfers.iter().filter(|t| fills(request, t)) {
+                if self
+                    .store
+                    .find_by_transaction(&transaction.bank, &transaction.id)?
+                    .is_none()
+                {
+                    found = Some(transaction.clone());
+                    break;
+                }
+            }
+
+            // One failed fill must not hold up the others, the request stays
+            // open and is tried again on the next poll.
+            if let Some(transaction) = found {
+                if let Err(error) = self.fill(request_id, transaction, &rate_quote).await {
+                    log::error!("filling request {:#x} failed: {}", request_id, error);
+                    self.store
+                        .record_open_request_failure(&request_id, &error.to_string())?;
+                    if let Some(request) = self.open.get_mut(&request_id) {
+                        request.error = Some(error.to_string());
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds the `RequestOfframp` logs emitted since the last poll to the open
+    /// requests.
+    async fn watch_requests(&mut self) -> Result<(), OfframpError> {
+        let latest = self
+            .signer
+            .get_block_number()
+            .await
+            .map_err(|e| OfframpError::Rpc(e.to_string()))?
+            .as_u64();
+        if latest < self.next_block {
+            return Ok(());
+        }
+
+        let events = self
+            .contract
+            .request_offramp_filter()
+            .from_block(self.next_block)
+            .to_block(latest)
+            .query_with_meta()
+            .await
+            .map_err(OfframpError::from_contract_error)?;
+
+        for (event, meta) in events {
+            let request_id = H256(event.request_offramp_id);
+            if self
+                .store
+                .find_by_request_offramp_id(&format!("{:#x}", request_id))?
+                .is_some()
+            {
+                continue;
+            }
+
+            let Some(&channel_id) = CHANNEL_IDS
+                .iter()
+                .find(|channel_id| hashed_channel_id(channel_id) == event.params.channel_id)
+            else {
+                log::warn!("request {:#x} is on an unknown channel", request_id);
+                continue;
+            };
+
+            if !prices_like(&event.params, &self.rate_quote) {
+                log::warn!(
+                    "request {:#x} asks {} for {} minor units, which the rate quote doesn't price alike yet",
+                    request_id,
+                    event.params.amount,
+                    event.params.amount_real_world
+                );
+            }
+
+            let block = self
+                .signer
+                .get_block(meta.block_number)
+                .await
+                .map_err(|e| OfframpError::Rpc(e.to_string()))?
+                .ok_or_else(|| {
+                    OfframpError::Rpc(format!("block {} not found", meta.block_number))
+                })?;
+
+            println!(
+                "Request {:#x}: {:#x} wants {} minor units on a {} account",
+                request_id, event.params.user, event.params.amount_real_world, channel_id
+            );
+            let request = OpenRequest {
+                request_offramp_id: request_id,
+                params: event.params,
+                channel_id: String::from(channel_id),
+                requested_at: block.timestamp.low_u32(),
+                error: None,
+            };
+            self.store.insert_open_request(&request)?;
+            self.open.insert(request_id, request);
+        }
+
+        self.next_block = latest + 1;
+        self.store
+            .set_next_block(REQUEST_OFFRAMP_CURSOR, self.next_block)?;
+        Ok(())
+    }
+
+    /// Reloads the rate quote from `--rate-url`, or the `--rate-quote` file,
+    /// and returns it when the program would still accept it for a claim
+    /// signed now. The program rejects quotes more than [`MAX_RATE_AGE_S`]
+    /// away from the claim. Now is the latest block's timestamp, which on a
+    /// local node follows the mock bank's dates.
+    async fn refresh_rate_quote(&mut self) -> Result<Option<types::RateQuote>, OfframpError> {
+        let now = self
+            .signer
+            .get_block(BlockNumber::Latest)
+            .await
+            .map_err(|e| OfframpError::Rpc(e.to_string()))?
+            .ok_or_else(|| OfframpError::Rpc(String::from("latest block not found")))?
+            .timestamp
+            .low_u32();
+        self.rate_quote = match &self.args.rate_url {
+            Some(rate_url) => self
+                .bank
+                .get(rate_url)
+                .query(&[
+                    ("pair", self.rate_quote.pair.clone()),
+                    ("timestampS", now.to_string()),
+                ])
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await
+                .map_err(|e| OfframpError::Schema(format!("rate quote: {}", e)))?,
+            None => load_rate_quote(&self.args.program.rate_quote)?,
+        };
+
+        if now.abs_diff(self.rate_quote.timestamp) + RATE_QUOTE_MARGIN_S > MAX_RATE_AGE_S {
+            return Ok(None);
+        }
+        Ok(Some(self.rate_quote.clone()))
+    }
+
+    /// The operator's outgoing bank transfers, parsed like the responses of
+    /// `--transfers-channel-id`.
+    async fn fetch_transfers(&self) -> Result<Vec<types::Transaction>, OfframpError> {
+        let response = self
+            .bank
+            .get(&self.args.transfers_url)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        bank_provider(&self.args.transfers_channel_id)?.parse_response(&response)
+    }
+
+    /// Fetches the Reclaim proof of `transaction` and takes the fill of
+    /// `request_id` as far as `--mode`.
+    async fn fill(
+        &mut self,
+        request_id: H256,
+        transaction: types::Transaction,
+        rate_quote: &types::RateQuote,
+    ) -> Result<(), OfframpError> {
+        let request = &self.open[&request_id];
+        let (.., is_proved, _, _) = self
+            .contract
+            .offramp_requests(request_id.0)
+            .call()
+            .await
+            .map_err(OfframpError::from_contract_error)?;
+        if is_proved {
+            println!("Request {:#x} was filled by someone else", request_id);
+            self.store.remove_open_request(&request_id)?;
+            self.open.remove(&request_id);
+            return Ok(());
+        }
+
+        println!(
+            "Filling request {:#x} with {} transaction {}",
+            request_id, request.channel_id, transaction.id
+        );
+
+        // The program commits the proof's `contextAddress` as the request's
+        // `user`, so the context has to name the requester for the public
+        // values to hash to `request_id`.
+        let recipient = format!("{:#x}", request.params.user);
+        let transfer_request =
+            bank_provider(&request.channel_id)?.transfer_request(&transaction.id, &recipient);
+        let proof = self
+            .attestor
+            .fetch_transfer_proof(&transfer_request, &self.authorization_token)
+            .await?;
+
+        let job = self.store.insert(JobRequest {
+            proof,
+            recipient,
+            channel_id: transaction.bank,
+            transaction_id: transaction.id,
+            request_timestamp: request.requested_at,
+            rate_quote: rate_quote.clone(),
+            mode: self.args.mode,
+            request_offramp_id: Some(format!("{:#x}", request_id)),
+        })?;
+        self.store.remove_open_request(&request_id)?;
+        self.open.remove(&request_id);
+
+        self.settle(&job).await;
+        Ok(())
+    }
+
+    /// Takes `job` through its remaining stages, recording a failure instead
+    /// of returning it so one bad fill doesn't stop the solver.
+    async fn settle(&self, job: &Job) {
+        match self.advance(job).await {
+            Ok(()) => {}
+            Err(error) => {
+                log::error!("job {} failed: {}", job.id, error);
+                if let Err(error) = self.store.record_failure(&job.id, &error.to_string()) {
+                    log::error!("failed to record the failure of job {}: {}", job.id, error);
+                }
+            }
+        }
+    }
+
+    async fn advance(&self, job: &Job) -> Result<(), OfframpError> {
+        // Executing and proving block for minutes, run them off the async
+        // runtime like the server's workers.
+        let (prover, store, proving) = (
+            Arc::clone(&self.prover),
+            Arc::clone(&self.store),
+            job.clone(),
+        );
+        tokio::task::spawn_blocking(move || prover.run(&store, &proving))
+            .await
+            .unwrap_or_else(|e| Err(OfframpError::Proving(e.to_string())))?;
+        if job.mode != JobMode::Claim {
+            println!("Job {} reached stage {:?}", job.id, job.mode.target());
+            return Ok(());
+        }
+
+        let job = self
+            .store
+            .get(&job.id)?
+            .ok_or_else(|| OfframpError::Input(format!("unknown job {}", job.id)))?;
+        let proof = load_sp1_proof(&self.store.proof_path(&job.id))?;
+        let aligned_data_path = self.store.aligned_data_path(&job.id);
+
+        if job.stage == Stage::Proved {
+            // The verification data is written before the stage is recorded,
+            // so a crash in between must not pay Aligned a second time.
+            let aligned_verification_data = if aligned_data_path.exists() {
+                load_aligned_verification_data(&aligned_data_path)?
+            } else {
+                preflight(&self.contract, &proof).await?;
+                let Some(aligned_verification_data) = submit(
+                    &self.signer,
+                    &self.answers,
+                    &self.args.wallet,
+                    &self.args.batcher_url,
+                    self.max_fee_ceiling,
+                    self.elf,
+                    &proof,
+                    self.args.program.mock,
+                )
+                .await?
+                else {
+                    println!("Job {} waits for the Aligned fee to drop", job.id);
+                    return Ok(());
+                };
+                save_aligned_verification_data(&aligned_data_path, &aligned_verification_data)?;
+                aligned_verification_data
+            };
+            self.store.record_submission(
+                &job.id,
+                &format!(
+                    "0x{}",
+                    hex::encode(aligned_verification_data.batch_merkle_root)
+                ),
+            )?;
+        }
+
+        let aligned_verification_data = load_aligned_verification_data(&aligned_data_path)?;
+        // `fillOfframp` marks the proof used, so a fill that went through
+        // before a crash or a failed receipt is recorded, not sent again.
+        let used = self
+            .contract
+            .used_proofs(proof_hash(&aligned_verification_data))
+            .call()
+            .await
+            .map_err(OfframpError::from_contract_error)?;
+        if used {
+            println!("Job {} was already claimed", job.id);
+            self.store.record_claim(&job.id, None)?;
+            return Ok(());
+        }
+
+        let tx_hash = claim(
+            &aligned_verification_data,
+            self.signer.clone(),
+            &self.args.jackramp_contract_address,
+            &proof,
+            false,
+            self.args.program.mock,
+        )
+        .await?;
+        self.store
+            .record_claim(&job.id, Some(&format!("{:#x}", tx_hash)))?;
+
+        Ok(())
+    }
+}
+
+/// Whether `transaction` is the bank transfer `request` asks for: same
+/// channel, account and amount, made after the request. Transfers dated only
+/// to the day count as made by the end of that day.
+fn fills(request: &OpenRequest, transaction: &types::Transaction) -> bool {
+    hashed_channel_id(&transaction.bank) == request.params.channel_id
+        && keccak256(&transaction.to) == request.params.channel_account
+        && U256::from(transaction.amount.minor_units()) == request.params.amount_real_world
+        && parse_transfer_period(&transaction.transfer_date)
+            .is_some_and(|(_, transferred_by)| transferred_by >= request.requested_at)
+}
+
+/// Whether the program, pricing the request's real world amount with
+/// `rate_quote`, commits the stablecoin amount the user asked for.
+fn prices_like(params: &OfframpRequestParams, rate_quote: &types::RateQuote) -> bool {
+    params.amount_real_world <= U256::from(u128::MAX)
+        && price(
+            Amount::from_minor_units(params.amount_real_world.as_u128()),
+            rate_quote,
+        )
+        .is_ok_and(|amount| amount == params.amount)
+}
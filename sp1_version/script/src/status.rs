@@ -0,0 +1,127 @@
+use ethers::providers::Middleware;
+use ethers::types::{Address, H256, U256};
+use serde_json::{json, Value};
+use std::fmt;
+use zktransfer_lib::banks::{hashed_channel_id, CHANNEL_IDS};
+
+use crate::contract::JackRampContract;
+use crate::error::OfframpError;
+
+/// An entry of `offrampRequests`, as stored by `JackRampContract`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OfframpRequestState {
+    pub request_offramp_id: H256,
+    pub user: Address,
+    pub amount: U256,
+    pub amount_real_world: U256,
+    pub hashed_channel_account: H256,
+    pub hashed_channel_id: H256,
+    pub is_proved: bool,
+    /// `usedProofs` key of the proof that filled the request.
+    pub proof: H256,
+    /// `hashedClaimInfo` of the Reclaim proof that filled the request.
+    pub reclaim_proof: H256,
+}
+
+impl OfframpRequestState {
+    /// Reads request `request_offramp_id` with an `eth_call`.
+    pub async fn fetch<M: Middleware>(
+        contract: &JackRampContract<M>,
+        request_offramp_id: H256,
+    ) -> Result<Self, OfframpError> {
+        let (
+            user,
+            amount,
+            amount_real_world,
+            hashed_channel_account,
+            hashed_channel_id,
+            is_proved,
+            proof,
+            reclaim_proof,
+        ) = contract
+            .offramp_requests(request_offramp_id.0)
+            .call()
+            .await
+            .map_err(OfframpError::from_contract_error)?;
+
+        Ok(OfframpRequestState {
+            request_offramp_id,
+            user,
+            amount,
+            amount_real_world,
+            hashed_channel_account: H256(hashed_channel_account),
+            hashed_channel_id: H256(hashed_channel_id),
+            is_proved,
+            proof: H256(proof),
+            reclaim_proof: H256(reclaim_proof),
+        })
+    }
+
+    /// Unknown ids read back as an all zero entry.
+    pub fn exists(&self) -> bool {
+        self.user != Address::zero()
+    }
+
+    /// The plaintext channel id, when it is a known bank.
+    pub fn channel_id(&self) -> Option<&'static str> {
+        CHANNEL_IDS
+            .iter()
+            .find(|channel_id| H256(hashed_channel_id(channel_id)) == self.hashed_channel_id)
+            .copied()
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "requestOfframpId": format!("{:#x}", self.request_offramp_id),
+            "exists": self.exists(),
+            "user": format!("{:#x}", self.user),
+            "amount": self.amount.to_string(),
+            "amountRealWorld": self.amount_real_world.to_string(),
+            "hashedChannelAccount": format!("{:#x}", self.hashed_channel_account),
+            "hashedChannelId": format!("{:#x}", self.hashed_channel_id),
+            "channelId": self.channel_id(),
+            "isProved": self.is_proved,
+            "proof": format!("{:#x}", self.proof),
+            "reclaimProof": format!("{:#x}", self.reclaim_proof),
+        })
+    }
+}
+
+impl fmt::Display for OfframpRequestState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "requestOfframpId: {:#x}", self.request_offramp_id)?;
+        if !self.exists() {
+            return write!(f, "status: does not exist");
+        }
+
+        let status = if self.is_proved { "filled" } else { "open" };
+        writeln!(f, "status: {}", status)?;
+        writeln!(f, "user: {:#x}", self.user)?;
+        writeln!(f, "amount: {}", self.amount)?;
+        writeln!(
+            f,
+            "amount real world: {} minor units",
+            self.amount_real_world
+        )?;
+        writeln!(
+            f,
+            "channel id: {} ({:#x})",
+            self.channel_id().unwrap_or("unknown"),
+            self.hashed_channel_id
+        )?;
+        write!(
+            f,
+            "hashed channel account: {:#x}",
+            self.hashed_channel_account
+        )?;
+        if self.is_proved {
+            write!(
+                f,
+                "\nproof: {:#x}\nreclaim proof: {:#x}",
+                self.proof, self.reclaim_proof
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+//! On-chain settlement against a local anvil node. Needs `anvil` on the
+//! `PATH`: `cargo test --test anvil -- --ignored`.
+
+use alloy_sol_types::SolType;
+use ethers::abi::{encode, AbiDecode, Token};
+use ethers::prelude::*;
+use ethers::utils::{Anvil, AnvilInstance};
+use script::artifact::load_plonk_fixture;
+use script::contract::plonk_verifier::SP1VerifierPlonk;
+use script::contract::{FillOfframpWithSp1ProofCall, JackRampGatewayContract, SP1VerifierGateway};
+use script::evm::EvmSigner;
+use script::onchain::{fill_with_sp1_proof, verify_onchain};
+use script::OfframpError;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use zktransfer_lib::BatchPublicValuesStruct;
+
+// Creation code of a contract that accepts every call and of one that
+// reverts on every call, standing in for the gateway variant of the contract.
+const ACCEPT_ALL: &str = "6001600c60003960016000f300";
+const REJECT_ALL: &str = "6005600c60003960056000f360006000fd";
+
+fn signer(anvil: &AnvilInstance) -> EvmSigner {
+    let provider = Provider::<Http>::try_from(anvil.endpoint())
+        .unwrap()
+        .interval(Duration::from_millis(10));
+    let wallet: LocalWallet = anvil.keys()[0].clone().into();
+
+    SignerMiddleware::new(provider, wallet.with_chain_id(anvil.chain_id()))
+}
+
+async fn deploy(signer: &EvmSigner, creation_code: &str) -> Address {
+    let deployment = TransactionRequest::new().data(hex::decode(creation_code).unwrap());
+
+    signer
+        .send_transaction(deployment, None)
+        .await
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap()
+        .contract_address
+        .unwrap()
+}
+
+#[tokio::test]
+#[ignore]
+async fn fills_send_the_public_values_and_proof() {
+    let anvil = Anvil::new().spawn();
+    let signer = Arc::new(signer(&anvil));
+    let gateway = JackRampGatewayContract::new(deploy(&signer, ACCEPT_ALL).await, signer.clone());
+    let public_values = Bytes::from(vec![0x01; 64]);
+    let proof_bytes = Bytes::from(vec![0xab; 32]);
+
+    let tx_hash = fill_with_sp1_proof(&gateway, public_values.clone(), proof_bytes.clone(), false)
+        .await
+        .unwrap();
+
+    let tx = signer.get_transaction(tx_hash).await.unwrap().unwrap();
+    let call = FillOfframpWithSp1ProofCall::decode(&tx.input).unwrap();
+    assert_eq!(call.public_values, public_values);
+    assert_eq!(call.proof_bytes, proof_bytes);
+
+    let batch = Bytes::from(BatchPublicValuesStruct::abi_encode(
+        &BatchPublicValuesStruct { items: Vec::new() },
+    ));
+    assert!(matches!(
+        fill_with_sp1_proof(&gateway, batch, proof_bytes.clone(), false).await,
+        Err(OfframpError::Input(_))
+    ));
+
+    let rejecting = JackRampGatewayContract::new(deploy(&signer, REJECT_ALL).await, signer);
+    assert!(
+        fill_with_sp1_proof(&rejecting, public_values, proof_bytes, false)
+            .await
+            .is_err()
+    );
+}
+
+/// Verifies a fixture written by `evm prove --settlement plonk --plonk-fixture`
+/// through the SP1 verifier gateway routing to the PLONK verifier, both
+/// deployed from the forge artifacts of succinctlabs/sp1-contracts given in
+/// `SP1_VERIFIER_GATEWAY_ARTIFACT` and `SP1_VERIFIER_ARTIFACT`, the fixture
+/// being in `JACKRAMP_PLONK_FIXTURE`.
+#[tokio::test]
+#[ignore]
+async fn sp1_verifier_gateway_accepts_plonk_fixtures() {
+    let vars = [
+        "SP1_VERIFIER_GATEWAY_ARTIFACT",
+        "SP1_VERIFIER_ARTIFACT",
+        "JACKRAMP_PLONK_FIXTURE",
+    ];
+    let missing: Vec<&str> = vars
+        .into_iter()
+        .filter(|var| std::env::var(var).is_err())
+        .collect();
+    assert!(
+        missing.is_empty(),
+        "set {} to run this test",
+        missing.join(", ")
+    );
+    let [gateway_artifact, verifier_artifact, fixture_path] =
+        vars.map(|var| std::env::var(var).unwrap());
+
+    let anvil = Anvil::new().spawn();
+    let signer = Arc::new(signer(&anvil));
+    let owner = encode(&[Token::Address(signer.address())]);
+    let gateway = SP1VerifierGateway::new(
+        deploy(
+            &signer,
+            &format!("{}{}", creation_code(&gateway_artifact), hex::encode(owner)),
+        )
+        .await,
+        signer.clone(),
+    );
+    let verifier = SP1VerifierPlonk::new(
+        deploy(&signer, &creation_code(&verifier_artifact)).await,
+        signer.clone(),
+    );
+    gateway
+        .add_route(verifier.address())
+        .send()
+        .await
+        .unwrap()
+        .await
+        .unwrap();
+
+    let fixture = load_plonk_fixture(Path::new(&fixture_path)).unwrap();
+    let program_vkey = H256::from_str(&fixture.vkey).unwrap().to_fixed_bytes();
+    let public_values = Bytes::from_str(&fixture.public_values).unwrap();
+    let proof_bytes = Bytes::from_str(&fixture.proof).unwrap();
+
+    // The gateway routes on the first 4 bytes of the verifier hash.
+    let verifier_hash = verifier.verifier_hash().call().await.unwrap();
+    assert_eq!(proof_bytes[..4], verifier_hash[..4]);
+
+    verify_onchain(
+        &gateway,
+        program_vkey,
+        public_values.clone(),
+        proof_bytes.clone(),
+    )
+    .await
+    .unwrap();
+
+    let mut tampered = public_values.to_vec();
+    *tampered.last_mut().unwrap() ^= 1;
+    let error = verify_onchain(
+        &gateway,
+        program_vkey,
+        Bytes::from(tampered),
+        proof_bytes.clone(),
+    )
+    .await
+    .unwrap_err();
+    assert!(error.to_string().contains("InvalidProof"), "{}", error);
+
+    let unprefixed = Bytes::from(proof_bytes[4..].to_vec());
+    let error = verify_onchain(&gateway, program_vkey, public_values, unprefixed)
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("RouteNotFound"), "{}", error);
+}
+
+/// The creation code of a forge artifact.
+fn creation_code(artifact_path: &str) -> String {
+    let artifact: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(artifact_path).unwrap()).unwrap();
+
+    artifact["bytecode"]["object"]
+        .as_str()
+        .unwrap()
+        .trim_start_matches("0x")
+        .to_string()
+}
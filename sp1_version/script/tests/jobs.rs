@@ -0,0 +1,75 @@
+use rand::Rng;
+use rusqlite::Connection;
+use script::jobs::{JobMode, JobRequest, JobStore, Stage};
+use script::types;
+use std::path::PathBuf;
+
+fn data_dir() -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "jackramp-jobs-{}",
+        hex::encode(rand::thread_rng().gen::<[u8; 8]>())
+    ))
+}
+
+fn request() -> JobRequest {
+    JobRequest {
+        proof: types::Proof::default(),
+        recipient: String::from("0x00000000000000000000000000000000000000aa"),
+        channel_id: String::from("bca"),
+        transaction_id: String::from("121"),
+        request_timestamp: 1728950400,
+        rate_quote: types::RateQuote::default(),
+        mode: JobMode::Claim,
+        request_offramp_id: None,
+    }
+}
+
+#[test]
+fn failed_jobs_resume_from_their_last_stage() {
+    let data_dir = data_dir();
+    let store = JobStore::open(&data_dir).unwrap();
+    let job = store.insert(request()).unwrap();
+
+    store
+        .record_execution(&job.id, 42, &[1, 2, 3], "0xAB")
+        .unwrap();
+    store.record_proof(&job.id).unwrap();
+    store.record_failure(&job.id, "aligned error").unwrap();
+
+    let failed = store.get(&job.id).unwrap().unwrap();
+    assert_eq!(failed.stage, Stage::Failed);
+    assert_eq!(failed.last_stage, Stage::Proved);
+    assert!(store.unfinished().unwrap().is_empty());
+
+    assert_eq!(store.retry_failed().unwrap(), 1);
+    let retried = store.get(&job.id).unwrap().unwrap();
+    assert_eq!(retried.stage, Stage::Proved);
+    assert_eq!(retried.error, None);
+    assert_eq!(retried.cycles, Some(42));
+    assert_eq!(store.unfinished().unwrap()[0].id, job.id);
+    assert_eq!(store.retry_failed().unwrap(), 0);
+
+    std::fs::remove_dir_all(data_dir).unwrap();
+}
+
+#[test]
+fn databases_without_last_stage_are_migrated() {
+    let data_dir = data_dir();
+    std::fs::create_dir_all(&data_dir).unwrap();
+    Connection::open(data_dir.join("jobs.sqlite"))
+        .unwrap()
+        .execute_batch(
+            "CREATE TABLE jobs (id TEXT PRIMARY KEY, request TEXT NOT NULL, stage TEXT NOT NULL, cycles INTEGER, public_values BLOB, request_offramp_id TEXT, batch_merkle_root TEXT, tx_hash TEXT, error TEXT, created_at INTEGER NOT NULL, updated_at INTEGER NOT NULL);",
+        )
+        .unwrap();
+
+    let store = JobStore::open(&data_dir).unwrap();
+    let job = store.insert(request()).unwrap();
+    store.record_failure(&job.id, "execution error").unwrap();
+    assert_eq!(
+        store.get(&job.id).unwrap().unwrap().last_stage,
+        Stage::Fetched
+    );
+
+    std::fs::remove_dir_all(data_dir).unwrap();
+}
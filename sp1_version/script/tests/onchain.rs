@@ -0,0 +1,70 @@
+use ethers::abi::{AbiDecode, AbiEncode};
+use ethers::types::Bytes;
+use script::contract::VerifyProofCall;
+use script::onchain::encode_plonk_proof;
+use script::revert::describe_verifier_revert;
+
+// `verifyProof(bytes32,bytes,bytes)` of `ISP1Verifier`.
+const VERIFY_PROOF_SELECTOR: [u8; 4] = [0x41, 0x49, 0x3c, 0x60];
+const PLONK_VKEY_HASH: [u8; 32] = [
+    0xd4, 0xe8, 0xec, 0xd2, 0x35, 0x7d, 0xfa, 0x18, 0x90, 0xa1, 0xe3, 0x6c, 0x0c, 0x07, 0xd8, 0x68,
+    0xd1, 0x6d, 0x03, 0x10, 0x2c, 0xbb, 0x2a, 0x75, 0xe6, 0x4e, 0xb1, 0x17, 0x7a, 0xb0, 0x58, 0x92,
+];
+const ENCODED_PROOF: &str = "0x2b5e3f9a0c7d11e4";
+
+// Revert data of the SP1 verifier gateway and the PLONK verifier.
+const ROUTE_NOT_FOUND: &str =
+    "f208777e2b5e3f9a00000000000000000000000000000000000000000000000000000000";
+const WRONG_VERIFIER_SELECTOR: &str = concat!(
+    "988066a1",
+    "2b5e3f9a00000000000000000000000000000000000000000000000000000000",
+    "d4e8ecd200000000000000000000000000000000000000000000000000000000",
+);
+const INVALID_PROOF: &str = "09bde339";
+
+#[test]
+fn plonk_proof_bytes_start_with_the_vkey_hash_selector() {
+    let proof_bytes = encode_plonk_proof(&PLONK_VKEY_HASH, ENCODED_PROOF).unwrap();
+
+    assert_eq!(proof_bytes[..4], PLONK_VKEY_HASH[..4]);
+    assert_eq!(
+        proof_bytes[4..],
+        hex::decode(ENCODED_PROOF.trim_start_matches("0x")).unwrap()
+    );
+    assert_eq!(
+        encode_plonk_proof(&PLONK_VKEY_HASH, ENCODED_PROOF.trim_start_matches("0x")).unwrap(),
+        proof_bytes
+    );
+    assert!(encode_plonk_proof(&PLONK_VKEY_HASH, "0xnot hex").is_err());
+}
+
+#[test]
+fn verify_proof_calls_keep_the_selector_on_the_proof_bytes() {
+    let call = VerifyProofCall {
+        program_v_key: [0x11; 32],
+        public_values: Bytes::from(vec![0x22; 40]),
+        proof_bytes: encode_plonk_proof(&PLONK_VKEY_HASH, ENCODED_PROOF).unwrap(),
+    };
+    let calldata = call.clone().encode();
+
+    assert_eq!(calldata[..4], VERIFY_PROOF_SELECTOR);
+    let decoded = VerifyProofCall::decode(&calldata).unwrap();
+    assert_eq!(decoded, call);
+    assert_eq!(decoded.proof_bytes[..4], PLONK_VKEY_HASH[..4]);
+}
+
+#[test]
+fn verifier_reverts_decode() {
+    let describe = |data: &str| describe_verifier_revert(&hex::decode(data).unwrap()).unwrap();
+
+    assert_eq!(
+        describe(ROUTE_NOT_FOUND),
+        "RouteNotFound(selector: 0x2b5e3f9a): the gateway has no verifier for the SP1 version of the proof"
+    );
+    assert_eq!(
+        describe(WRONG_VERIFIER_SELECTOR),
+        "WrongVerifierSelector(received: 0x2b5e3f9a, expected: 0xd4e8ecd2)"
+    );
+    assert!(describe(INVALID_PROOF).starts_with("InvalidProof:"));
+    assert!(describe_verifier_revert(&hex::decode("d8cc96d5").unwrap()).is_none());
+}
@@ -0,0 +1,214 @@
+//! The fetch, prove, submit and claim flow against the mock bank, with the
+//! mock prover and stubbed Aligned. Claiming needs `anvil` on the `PATH`:
+//! `cargo test --test pipeline -- --ignored`.
+
+use aligned_sdk::core::types::{AlignedVerificationData, Network};
+use ethers::abi::AbiDecode;
+use ethers::prelude::*;
+use ethers::utils::Anvil;
+use mock::{Mock, CLAIM_DELAY_S, MUTATIONS};
+use script::artifact::load_sp1_proof;
+use script::config::Answers;
+use script::contract::FillOfframpCall;
+use script::evm::{claim, proof_hash, submit, EvmSigner, WalletArgs};
+use script::jobs::{JobMode, JobProver, JobRequest, JobStore, Stage};
+use script::{is_mock_proof, types, OfframpError, DEFAULT_MAX_TRANSFER_AGE};
+use sp1_sdk::SP1ProofWithPublicValues;
+use std::time::Duration;
+use zktransfer_lib::decode_public_values;
+
+const ZKTRANSFER_ELF: &[u8] = include_bytes!("../../program/elf/riscv32im-succinct-zkvm-elf");
+
+const RECIPIENT: &str = "0x00000000000000000000000000000000000000aa";
+
+// Creation code of a contract that accepts every call, standing in for
+// `JackRampContract` on top of stubbed Aligned.
+const ACCEPT_ALL: &str = "6001600c60003960016000f300";
+
+/// Fetches the mock Reclaim proof of BCA transfer `121` and proves it with
+/// the mock prover, through the same job pipeline as the server and solver.
+fn fetch_and_prove(name: &str) -> SP1ProofWithPublicValues {
+    let mock = Mock::new(
+        "http://localhost:7787",
+        serde_json::from_str(MUTATIONS).unwrap(),
+    );
+    let proof = mock
+        .transfer_proof(&types::TransferRequest {
+            id: String::from("121"),
+            bank: String::from("bca"),
+            address: String::from(RECIPIENT),
+        })
+        .unwrap();
+    let claim_timestamp = proof.signed_claim.claim.timestamp;
+
+    let data_dir = std::env::temp_dir().join(format!("jackramp-{}-{}", name, std::process::id()));
+    let store = JobStore::open(&data_dir).unwrap();
+    let job = store
+        .insert(JobRequest {
+            proof,
+            recipient: String::from(RECIPIENT),
+            channel_id: String::from("bca"),
+            transaction_id: String::from("121"),
+            request_timestamp: claim_timestamp - CLAIM_DELAY_S,
+            rate_quote: mock.sign_rate_quote("IDR/USD", claim_timestamp),
+            mode: JobMode::Prove,
+            request_offramp_id: None,
+        })
+        .unwrap();
+
+    let prover = JobProver::new(
+        ZKTRANSFER_ELF,
+        vec![Address::from(mock.witness.address().into_array())],
+        vec![Address::from(mock.oracle.address().into_array())],
+        DEFAULT_MAX_TRANSFER_AGE,
+        true,
+    );
+    prover.run(&store, &job).unwrap();
+
+    let job = store.get(&job.id).unwrap().unwrap();
+    assert_eq!(job.stage, Stage::Proved);
+    let proof = load_sp1_proof(&store.proof_path(&job.id)).unwrap();
+    std::fs::remove_dir_all(&data_dir).unwrap();
+
+    proof
+}
+
+/// Submits `proof` to an Aligned batcher and node nobody listens on.
+async fn submit_offline(
+    signer: &EvmSigner,
+    proof: &SP1ProofWithPublicValues,
+    mock: bool,
+) -> Result<Option<AlignedVerificationData>, OfframpError> {
+    let wallet_args = WalletArgs {
+        keystore_path: String::new(),
+        rpc_url: String::from("http://127.0.0.1:1"),
+        network: Network::Devnet,
+        keystore_password: None,
+    };
+
+    submit(
+        signer,
+        &Answers::default(),
+        &wallet_args,
+        "ws://127.0.0.1:1",
+        None,
+        ZKTRANSFER_ELF,
+        proof,
+        mock,
+    )
+    .await
+}
+
+async fn stub_submit(
+    signer: &EvmSigner,
+    proof: &SP1ProofWithPublicValues,
+) -> AlignedVerificationData {
+    submit_offline(signer, proof, true).await.unwrap().unwrap()
+}
+
+fn signer(rpc_url: &str, wallet: LocalWallet) -> EvmSigner {
+    let provider = Provider::<Http>::try_from(rpc_url)
+        .unwrap()
+        .interval(Duration::from_millis(10));
+
+    SignerMiddleware::new(provider, wallet)
+}
+
+#[tokio::test]
+async fn mock_proofs_go_through_stubbed_aligned() {
+    let proof = fetch_and_prove("stubbed-aligned");
+    assert!(is_mock_proof(&proof));
+
+    let items = decode_public_values(proof.public_values.as_slice()).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(
+        format!("{:#x}", items[0].offrampRequestParams.user),
+        RECIPIENT
+    );
+
+    // Nothing listens on these urls: stubbed Aligned never reaches the network.
+    let wallet = LocalWallet::new(&mut rand::thread_rng());
+    let address = wallet.address();
+    let aligned_verification_data =
+        stub_submit(&signer("http://127.0.0.1:1", wallet), &proof).await;
+
+    let commitment = &aligned_verification_data.verification_data_commitment;
+    assert_eq!(commitment.proof_generator_addr, address.to_fixed_bytes());
+    assert_eq!(
+        aligned_verification_data.batch_merkle_root,
+        proof_hash(&aligned_verification_data)
+    );
+    assert!(aligned_verification_data
+        .batch_inclusion_proof
+        .merkle_path
+        .is_empty());
+}
+
+#[tokio::test]
+async fn mock_proofs_need_the_mock_flag() {
+    let proof = fetch_and_prove("mock-flag");
+    let signer = signer(
+        "http://127.0.0.1:1",
+        LocalWallet::new(&mut rand::thread_rng()),
+    );
+
+    assert!(matches!(
+        submit_offline(&signer, &proof, false).await,
+        Err(OfframpError::Input(_))
+    ));
+
+    let aligned_verification_data = stub_submit(&signer, &proof).await;
+    assert!(matches!(
+        claim(
+            &aligned_verification_data,
+            signer.clone(),
+            &Address::zero(),
+            &proof,
+            false,
+            false,
+        )
+        .await,
+        Err(OfframpError::Input(_))
+    ));
+}
+
+#[tokio::test]
+#[ignore]
+async fn mock_proofs_claim_on_anvil() {
+    let proof = fetch_and_prove("anvil");
+
+    let anvil = Anvil::new().spawn();
+    let wallet: LocalWallet = anvil.keys()[0].clone().into();
+    let signer = signer(&anvil.endpoint(), wallet.with_chain_id(anvil.chain_id()));
+    let aligned_verification_data = stub_submit(&signer, &proof).await;
+
+    let deployment = TransactionRequest::new().data(hex::decode(ACCEPT_ALL).unwrap());
+    let jackramp_contract_address = signer
+        .send_transaction(deployment, None)
+        .await
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap()
+        .contract_address
+        .unwrap();
+
+    let tx_hash = claim(
+        &aligned_verification_data,
+        signer.clone(),
+        &jackramp_contract_address,
+        &proof,
+        false,
+        true,
+    )
+    .await
+    .unwrap();
+
+    let tx = signer.get_transaction(tx_hash).await.unwrap().unwrap();
+    let call = FillOfframpCall::decode(&tx.input).unwrap();
+    assert_eq!(call.pub_input_bytes.to_vec(), proof.public_values.to_vec());
+    assert_eq!(
+        call.batch_merkle_root,
+        aligned_verification_data.batch_merkle_root
+    );
+}
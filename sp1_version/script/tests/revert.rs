@@ -0,0 +1,101 @@
+use ethers::types::{H160, U256};
+use script::contract::JackRampContractErrors;
+use script::revert::{decode_revert, describe_revert};
+use script::OfframpError;
+
+// Revert data of `fillOfframp` and `requestOfframp` failures, ABI-encoded by hand
+// from the contract's custom errors.
+const PUB_INPUT_COMMITMENT_MISMATCH: &str = "d8cc96d5";
+const PROOF_NOT_INCLUDED_IN_BATCH: &str = "b46d332e";
+const PROOF_GENERATOR_ADDR_MISMATCH: &str = "55007c61";
+const INVALID_RECLAIM_PROOF: &str = "caf6df51";
+const INVALID_ELF: &str =
+    "c6d950667b3a2c5d4e5f60718293a4b5c6d7e8f90112233445566778899aabbccddeeff0";
+const RECLAIM_PROOF_ALREADY_USED: &str =
+    "8a4db6f0a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f90";
+const ERC20_INSUFFICIENT_BALANCE: &str = concat!(
+    "e450d38c",
+    "0000000000000000000000002a14667e16d269eb326ee6fc4e9aaad23d094a9e",
+    "0000000000000000000000000000000000000000000000000000000000000064",
+    "00000000000000000000000000000000000000000000000000000000000003e8",
+);
+const REVERT_STRING: &str = concat!(
+    "08c379a0",
+    "0000000000000000000000000000000000000000000000000000000000000020",
+    "000000000000000000000000000000000000000000000000000000000000000e",
+    "6e6f7420617574686f72697a6564000000000000000000000000000000000000",
+);
+
+fn decode(data: &str) -> JackRampContractErrors {
+    decode_revert(&hex::decode(data).unwrap()).unwrap()
+}
+
+#[test]
+fn fill_reverts_without_fields_decode() {
+    assert!(matches!(
+        decode(PUB_INPUT_COMMITMENT_MISMATCH),
+        JackRampContractErrors::PubInputCommitmentMismatch(_)
+    ));
+    assert!(matches!(
+        decode(PROOF_NOT_INCLUDED_IN_BATCH),
+        JackRampContractErrors::ProofNotIncludedInBatch(_)
+    ));
+    assert!(matches!(
+        decode(PROOF_GENERATOR_ADDR_MISMATCH),
+        JackRampContractErrors::ProofGeneratorAddrMismatch(_)
+    ));
+    assert!(describe_revert(&decode(INVALID_RECLAIM_PROOF)).starts_with("InvalidReclaimProof:"));
+}
+
+#[test]
+fn fill_reverts_keep_their_fields() {
+    let JackRampContractErrors::InvalidElf(invalid_elf) = decode(INVALID_ELF) else {
+        panic!("expected InvalidElf");
+    };
+    assert_eq!(
+        hex::encode(invalid_elf.proving_system_aux_data_commitment),
+        &INVALID_ELF[8..]
+    );
+
+    let revert = decode(RECLAIM_PROOF_ALREADY_USED);
+    assert!(describe_revert(&revert).starts_with(&format!(
+        "ReclaimProofAlreadyUsed(hashedClaimInfo: 0x{})",
+        &RECLAIM_PROOF_ALREADY_USED[8..]
+    )));
+}
+
+#[test]
+fn erc20_reverts_keep_their_fields() {
+    let JackRampContractErrors::ERC20InsufficientBalance(error) =
+        decode(ERC20_INSUFFICIENT_BALANCE)
+    else {
+        panic!("expected ERC20InsufficientBalance");
+    };
+    assert_eq!(
+        error.sender,
+        "0x2a14667e16d269eb326ee6fc4e9aaad23d094a9e"
+            .parse::<H160>()
+            .unwrap()
+    );
+    assert_eq!(error.balance, U256::from(100));
+    assert_eq!(error.needed, U256::from(1000));
+}
+
+#[test]
+fn revert_strings_and_unknown_selectors() {
+    assert_eq!(
+        describe_revert(&decode(REVERT_STRING)),
+        "revert: not authorized"
+    );
+    assert!(decode_revert(&hex::decode("deadbeef").unwrap()).is_none());
+    assert!(decode_revert(&[]).is_none());
+}
+
+#[test]
+fn reverts_display_with_their_name() {
+    let error = OfframpError::Revert(decode(PUB_INPUT_COMMITMENT_MISMATCH));
+    assert_eq!(
+        error.to_string(),
+        "contract reverted with PubInputCommitmentMismatch: the public values are not the ones Aligned verified"
+    );
+}
@@ -0,0 +1,194 @@
+//! The solver against a local anvil node and the mock bank. Needs `anvil` on
+//! the `PATH`: `cargo test --test solver -- --ignored`.
+
+use alloy_sol_types::SolType;
+use clap::Parser;
+use ethers::abi::AbiEncode;
+use ethers::prelude::*;
+use ethers::types::BigEndianHash;
+use ethers::utils::{keccak256, Anvil};
+use mock::{Mock, MUTATIONS};
+use rand::Rng;
+use script::jobs::{JobMode, Stage};
+use script::request_offramp_id;
+use script::solver::{Solver, SolverArgs};
+use std::path::PathBuf;
+use std::sync::Arc;
+use zktransfer_lib::banks::hashed_channel_id;
+use zktransfer_lib::{decode_public_values, OfframpRequestParams};
+
+const ZKTRANSFER_ELF: &[u8] = include_bytes!("../../program/elf/riscv32im-succinct-zkvm-elf");
+
+// Anvil's genesis, before the mock transfers of 2024-10-15 00:00:00.
+const GENESIS_TIMESTAMP: u32 = 1728950000;
+
+const REQUESTER: &str = "0x00000000000000000000000000000000000000aa";
+
+/// Creation code of a stand-in for `JackRampContract`: any call emits
+/// `RequestOfframp(calldata[0..32], calldata[32..192])` and returns 256 zero
+/// bytes, i.e. an open `offrampRequests` entry.
+fn request_offramp_stub() -> String {
+    let topic = hex::encode(keccak256(
+        "RequestOfframp(bytes32,(address,uint256,uint256,bytes32,bytes32))",
+    ));
+
+    format!(
+        "6037600c60003960376000f360a060206000376000357f{}60a06000a2610100610100f3",
+        topic
+    )
+}
+
+fn params(amount: u64) -> OfframpRequestParams {
+    OfframpRequestParams {
+        user: REQUESTER.parse().unwrap(),
+        amount: alloy_primitives::U256::from(amount),
+        amountRealWorld: alloy_primitives::U256::from(100_000_000u64),
+        hashedChannelAccount: alloy_primitives::keccak256("111111"),
+        hashedChannelId: hashed_channel_id("bca").into(),
+    }
+}
+
+fn temp_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "jackramp-solver-{}",
+        hex::encode(rand::thread_rng().gen::<[u8; 8]>())
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[tokio::test]
+#[ignore]
+async fn solver_fills_requests_once_the_transfer_shows_up() {
+    let anvil = Anvil::new()
+        .arg("--timestamp")
+        .arg(GENESIS_TIMESTAMP.to_string())
+        .spawn();
+    let dir = temp_dir();
+
+    // The mock bank, attestor and oracle.
+    let mock = Mock::new("http://127.0.0.1", serde_json::from_str(MUTATIONS).unwrap());
+    let (witness, oracle) = (mock.witness.address(), mock.oracle.address());
+    let rate_quote_path = dir.join("rate.json");
+    std::fs::write(
+        &rate_quote_path,
+        serde_json::to_string(&mock.sign_rate_quote("IDR/USD", GENESIS_TIMESTAMP)).unwrap(),
+    )
+    .unwrap();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let mock_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, mock::http::router(Arc::new(mock)))
+            .await
+            .unwrap()
+    });
+
+    let provider = Provider::<Http>::try_from(anvil.endpoint()).unwrap();
+    let wallet: LocalWallet = anvil.keys()[0].clone().into();
+    let signer = SignerMiddleware::new(provider, wallet.with_chain_id(anvil.chain_id()));
+    let contract = signer
+        .send_transaction(
+            TransactionRequest::new().data(hex::decode(request_offramp_stub()).unwrap()),
+            None,
+        )
+        .await
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap()
+        .contract_address
+        .unwrap();
+
+    // 1,000,000.00 IDR at 64 token units per IDR, and a request the quote
+    // doesn't price, which stays open.
+    let priced = params(64_000_000);
+    let unpriced = params(1);
+    for params in [&priced, &unpriced] {
+        let mut calldata = request_offramp_id(params).encode();
+        calldata.extend(OfframpRequestParams::abi_encode(params));
+        signer
+            .send_transaction(TransactionRequest::new().to(contract).data(calldata), None)
+            .await
+            .unwrap()
+            .await
+            .unwrap();
+    }
+
+    let keystore_password = "solver";
+    LocalWallet::encrypt_keystore(
+        &dir,
+        &mut rand::thread_rng(),
+        anvil.keys()[0].to_bytes(),
+        keystore_password,
+        Some("keystore"),
+    )
+    .unwrap();
+
+    let args = SolverArgs::parse_from([
+        "solver",
+        "--keystore-path",
+        dir.join("keystore").to_str().unwrap(),
+        "--keystore-password",
+        keystore_password,
+        "--rpc-url",
+        anvil.endpoint().as_str(),
+        "--network",
+        "devnet",
+        "--jackramp-contract-address",
+        format!("{:#x}", contract).as_str(),
+        "--transfers-url",
+        format!("{}/api/v2/mutation?result=all", mock_url).as_str(),
+        "--rate-url",
+        format!("{}/api/v2/rate", mock_url).as_str(),
+        "--attestor-url",
+        mock_url.as_str(),
+        "--authorization-token",
+        "test",
+        "--witnesses",
+        format!("{:#x}", witness).as_str(),
+        "--oracles",
+        format!("{:#x}", oracle).as_str(),
+        "--rate-quote",
+        rate_quote_path.to_str().unwrap(),
+        "--mock",
+        "--mode",
+        "execute",
+        "--from-block",
+        "0",
+        "--data-dir",
+        dir.join("solver").to_str().unwrap(),
+        "--yes",
+    ]);
+    let mut solver = Solver::new(args, ZKTRANSFER_ELF).await.unwrap();
+    solver.poll().await.unwrap();
+
+    let priced_id = format!("{:#x}", H256::from_uint(&request_offramp_id(&priced)));
+    let job = solver
+        .store()
+        .find_by_request_offramp_id(&priced_id)
+        .unwrap()
+        .expect("the transfer of the priced request starts a fill");
+    assert_eq!(job.mode, JobMode::Execute);
+    assert_eq!(job.request.transaction_id, "121");
+    assert_eq!(job.request.recipient, REQUESTER);
+    assert_eq!(job.stage, Stage::Executed, "{:?}", job.error);
+
+    let items = decode_public_values(&job.public_values.unwrap()).unwrap();
+    assert_eq!(
+        request_offramp_id(&items[0].offrampRequestParams),
+        request_offramp_id(&priced)
+    );
+
+    let unpriced_id = H256::from_uint(&request_offramp_id(&unpriced));
+    let open: Vec<H256> = solver
+        .open_requests()
+        .map(|request| request.request_offramp_id)
+        .collect();
+    assert_eq!(open, vec![unpriced_id]);
+    assert_eq!(
+        solver.store().open_requests().unwrap()[0].request_offramp_id,
+        unpriced_id
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
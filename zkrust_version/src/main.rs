@@ -1,97 +1,58 @@
 #![allow(unused)]
 
-mod types;
-
-use zk_rust_io;
-use alloy_primitives::{keccak256, Address, Bytes, FixedBytes, B256, U256};
-use alloy_sol_types::{sol, SolType};
-use hex::FromHex;
+use alloy_primitives::Address;
 use reqwest;
+use std::env;
 use std::io;
+use zk_rust_io;
+use zktransfer_lib::types;
+use zktransfer_lib::verify::commit_offramps;
+use zktransfer_lib::{decode_public_values, PublicValuesStruct};
 
-sol! {
-    #[derive(Debug)]
-    struct CompleteClaimData {
-        bytes32 identifier;
-        address owner;
-        uint32 timestampS;
-        uint32 epoch;
-    }
-
-    #[derive(Debug)]
-    struct SignedClaim {
-        CompleteClaimData claim;
-        bytes[] signatures;
-    }
-
-    #[derive(Debug)]
-    struct PublicValuesStruct {
-      bytes32 hashedChannelId;
-      bytes32 hashedChannelAccount;
-      uint256 amount;
-      bytes32 hashedClaimInfo;
-      SignedClaim signedClaim;
-    }
-}
+// Same defaults as the sp1 script's `--witnesses` and `--max-transfer-age`.
+const DEFAULT_RECLAIM_WITNESS: &str = "0x244897572368eadf65bfbc5aec98d8e5443a9072";
+const DEFAULT_MAX_TRANSFER_AGE: u32 = 24 * 60 * 60;
 
+// Runs the same checks and commits the same public values as the sp1 program,
+// through `zktransfer_lib::verify`.
 fn main() {
-    let raw_proof_data: String = zk_rust_io::read();
-    let proof: types::Proof = serde_json::from_str(&raw_proof_data).unwrap();
-    let parameters: types::Parameters = serde_json::from_str(&proof.claim_info.parameters).unwrap();
-    let transaction: types::Data =
-        serde_json::from_str(&parameters.response_matches[0].value_resp).unwrap();
-
-    let mut encoded_claim_info: Vec<u8> = Vec::new();
-    encoded_claim_info.extend_from_slice(proof.claim_info.provider.as_bytes());
-    encoded_claim_info.extend_from_slice(b"\n");
-    encoded_claim_info.extend_from_slice(proof.claim_info.parameters.as_bytes());
-    encoded_claim_info.extend_from_slice(b"\n");
-    encoded_claim_info.extend_from_slice(proof.claim_info.context.as_bytes());
-
-    let hashed_claim_info: B256 = keccak256(encoded_claim_info);
-    let hashed_channel_id: B256 = keccak256(&transaction.data[0].bank);
-    let hashed_channel_account: B256 = keccak256(&transaction.data[0].to);
-    let amount: U256 = U256::from(transaction.data[0].amount);
-    let identifier = proof
-        .signed_claim
-        .claim
-        .identifier
-        .parse::<FixedBytes<32>>()
-        .unwrap();
-
-    let address_str = proof.signed_claim.claim.owner.strip_prefix("0x").unwrap();
-    let address_bytes: [u8; 20] = <[u8; 20]>::from_hex(address_str).expect("Invalid hex string");
-    let owner = Address::from(address_bytes);
-
-    let signatures = proof
-        .signed_claim
-        .signatures
-        .iter()
-        .map(|v| v.as_str().to_string().into())
-        .collect::<Vec<Bytes>>();
-
-    let bytes = PublicValuesStruct::abi_encode(&PublicValuesStruct {
-        hashedChannelId: hashed_channel_id,
-        hashedChannelAccount: hashed_channel_account,
-        amount: amount,
-        hashedClaimInfo: hashed_claim_info,
-        signedClaim: SignedClaim {
-            claim: CompleteClaimData {
-                epoch: proof.signed_claim.claim.epoch,
-                identifier: identifier,
-                owner: owner,
-                timestampS: proof.signed_claim.claim.timestamp,
-            },
-            signatures,
-        },
-    });
+    let witnesses: Vec<[u8; 20]> = zk_rust_io::read();
+    let oracles: Vec<[u8; 20]> = zk_rust_io::read();
+    let quote: types::RateQuote = zk_rust_io::read();
+    let max_transfer_age: u32 = zk_rust_io::read();
+    let inputs: Vec<types::OfframpInput> = zk_rust_io::read();
+
+    let witnesses = witnesses
+        .into_iter()
+        .map(Address::from)
+        .collect::<Vec<Address>>();
+    let oracles = oracles
+        .into_iter()
+        .map(Address::from)
+        .collect::<Vec<Address>>();
+    let bytes = commit_offramps(&witnesses, &oracles, &quote, max_transfer_age, &inputs)
+        .unwrap_or_else(|e| panic!("{}", e));
 
     zk_rust_io::commit(&bytes);
 }
 
+/// Parses a comma separated list of addresses, as the sp1 script's
+/// `--witnesses` and `--oracles`.
+fn addresses(list: &str) -> Vec<[u8; 20]> {
+    list.split(',')
+        .map(|address| {
+            address
+                .trim()
+                .parse::<Address>()
+                .expect("Invalid address")
+                .into_array()
+        })
+        .collect()
+}
+
 fn input() {
     println!("Welcome to the zkTransfer! Input the bank transaction, generate a zkProof, and claim your USD!");
-    println!("You will be asked to input Bank, Transaction ID, and Authorization token.");
+    println!("You will be asked to input Bank, Transaction ID, sender address, request timestamp, and Authorization token.");
 
     println!("{}", "What is your Channel ID?");
     let mut channel_id_answer = String::new();
@@ -107,6 +68,20 @@ fn input() {
         .read_line(&mut trx_id_answer)
         .expect("Failed to read from stdin");
 
+    println!("{}", "Input your Sender Address?");
+    let mut sender_address_answer = String::new();
+
+    io::stdin()
+        .read_line(&mut sender_address_answer)
+        .expect("Failed to read from stdin");
+
+    println!("{}", "Input your Request Timestamp?");
+    let mut request_timestamp_answer = String::new();
+
+    io::stdin()
+        .read_line(&mut request_timestamp_answer)
+        .expect("Failed to read from stdin");
+
     println!("{}", "Input your Authorization Token?");
     let mut trx_authorization_answer = String::new();
 
@@ -115,10 +90,9 @@ fn input() {
         .expect("Failed to read from stdin");
 
     println!(
-        "Answers {}:{}:{}",
+        "Answers {}:{}:***",
         &channel_id_answer.trim(),
-        &trx_id_answer.trim(),
-        &trx_authorization_answer.trim()
+        &trx_id_answer.trim()
     );
 
     let client = reqwest::blocking::Client::new();
@@ -126,15 +100,28 @@ fn input() {
     let request_body = types::TransferRequest {
         id: String::from(trx_id_answer.trim()),
         bank: String::from(channel_id_answer.trim()),
+        address: String::from(sender_address_answer.trim()),
     };
 
-    let source_url: String = String::from("https://mock.blocknaut.xyz/generateTransferProof");
+    // Same environment variables and defaults as the sp1 script's attestor client.
+    let attestor_url = env::var("JACKRAMP_ATTESTOR_URL")
+        .unwrap_or(String::from("https://mock.blocknaut.xyz"));
+    let proof_path = env::var("JACKRAMP_ATTESTOR_PROOF_PATH")
+        .unwrap_or(String::from("generateTransferProof"));
+    let auth_format =
+        env::var("JACKRAMP_ATTESTOR_AUTH_FORMAT").unwrap_or(String::from("Bearer {token}"));
+
+    let source_url: String = format!(
+        "{}/{}",
+        attestor_url.trim_end_matches('/'),
+        proof_path.trim_start_matches('/')
+    );
     let response = client
         .post(source_url)
         .header("Content-Type", "application/json")
         .header(
             "Authorization",
-            format!("Bearer {}", &trx_authorization_answer.trim()),
+            auth_format.replace("{token}", trx_authorization_answer.trim()),
         )
         .json(&request_body)
         .send()
@@ -160,13 +147,39 @@ fn input() {
         }
     };
 
+    // What the sp1 script takes as `--witnesses`, `--oracles`, `--rate-quote`
+    // and `--max-transfer-age`.
+    let witnesses = env::var("JACKRAMP_WITNESSES").unwrap_or(String::from(DEFAULT_RECLAIM_WITNESS));
+    let oracles = env::var("JACKRAMP_ORACLES").expect("JACKRAMP_ORACLES is not set");
+    let rate_quote_path = env::var("JACKRAMP_RATE_QUOTE").expect("JACKRAMP_RATE_QUOTE is not set");
+    let quote: types::RateQuote =
+        serde_json::from_str(&std::fs::read_to_string(rate_quote_path).unwrap())
+            .expect("Invalid rate quote file");
+    let max_transfer_age = env::var("JACKRAMP_MAX_TRANSFER_AGE")
+        .map(|age| age.parse::<u32>().expect("Invalid max transfer age"))
+        .unwrap_or(DEFAULT_MAX_TRANSFER_AGE);
+
+    let input = types::OfframpInput {
+        proof: serde_json::to_string(&proof).unwrap(),
+        transaction_id: String::from(trx_id_answer.trim()),
+        channel_id: String::from(channel_id_answer.trim()),
+        request_timestamp: request_timestamp_answer
+            .trim()
+            .parse::<u32>()
+            .expect("Invalid request timestamp"),
+    };
+
     println!("Generating Proof ");
 
-    zk_rust_io::write(&serde_json::to_string(&proof).unwrap());
+    zk_rust_io::write(&addresses(&witnesses));
+    zk_rust_io::write(&addresses(&oracles));
+    zk_rust_io::write(&quote);
+    zk_rust_io::write(&max_transfer_age);
+    zk_rust_io::write(&vec![input]);
 }
 
 fn output() {
     let output: Vec<u8> = zk_rust_io::out();
-    let decoded = PublicValuesStruct::abi_decode(output.as_slice(), true).unwrap();
+    let decoded: Vec<PublicValuesStruct> = decode_public_values(output.as_slice()).unwrap();
     println!("public value: {:?}", decoded);
 }
@@ -16,12 +16,13 @@ const app = express();
 app.use(express.json());
 const port = 7787;
 
-async function generateProof(url) {
+async function generateProof(url, context) {
   try {
     const proof = await reclaimClient.zkFetch(
       url,
       {
         method: "GET",
+        context,
       },
       3,
       5000
@@ -63,9 +64,16 @@ app.post("/generateTransferProof", async (req, res) => {
     return res.status(403).json({ error: "No credentials sent!" });
   }
 
+  if (!/^0x[0-9a-fA-F]{40}$/.test(req.body.address)) {
+    return res.status(400).json({ error: "'address' must be a 0x-prefixed address" });
+  }
+
   try {
+    // The recipient is embedded in the claim context so the witnesses sign it
+    // and the zkVM program can bind the offramp to it.
     const result = await generateProof(
-      `https://mock.blocknaut.xyz/api/v2/mutation?bank=${req.body.bank}&id=${req.body.id}`
+      `https://mock.blocknaut.xyz/api/v2/mutation?bank=${req.body.bank}&id=${req.body.id}`,
+      { contextAddress: req.body.address, contextMessage: req.body.id }
     );
 
     if (!result.success) {